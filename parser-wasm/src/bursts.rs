@@ -0,0 +1,197 @@
+use chrono::DateTime;
+use serde::{Serialize, Deserialize};
+
+use crate::histogram::Histogram;
+use crate::options::ParserOptions;
+use crate::timestamps::format_timestamp;
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// A window where errors arrived well above their usual rate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Burst {
+    /// Start of the first busy bucket and end of the last, RFC 3339 in UTC
+    pub start: String,
+    pub end: String,
+    /// Occurrences per minute in the busiest bucket of the window, all error groups together
+    pub peak_rate: f64,
+    /// Usual occurrences per minute across the log (median bucket)
+    pub baseline_rate: f64,
+    /// Error groups that spiked in the window, most occurrences first
+    pub fingerprints: Vec<String>,
+}
+
+/// Per-bucket counts of one series, with the count a bucket needs to be busy
+struct Series<'a> {
+    fingerprint: &'a str,
+    counts: Vec<u32>,
+    baseline: f64,
+    threshold: f64,
+}
+
+// ============================================================================
+// DETECTION
+// ============================================================================
+
+/// Find bursts in a histogram
+/// A bucket is busy for a series when it holds at least `burst_min_count` occurrences and
+/// `burst_factor` times the series' median bucket; a fingerprint spiking against its own
+/// history and the whole log spiking against the log's history both count
+/// Adjacent busy buckets form one burst
+pub fn detect(histogram: &Histogram, options: &ParserOptions) -> Vec<Burst> {
+    let Some(start) = histogram.start() else {
+        return Vec::new();
+    };
+    let bucket_seconds = histogram.bucket_seconds();
+    let min_count = options.burst_min_count.max(1);
+
+    let total = series("", histogram.total_series(), options);
+    let all_groups: Vec<Series> = histogram.fingerprints()
+        .filter_map(|fingerprint| histogram.series(fingerprint).map(|counts| series(fingerprint, counts, options)))
+        .collect();
+    // Groups with fewer occurrences than the minimum can't fill a busy bucket
+    let groups: Vec<&Series> = all_groups.iter()
+        .filter(|group| group.counts.iter().sum::<u32>() >= min_count)
+        .collect();
+
+    let busy = |bucket: usize| total.is_busy(bucket) || groups.iter().any(|group| group.is_busy(bucket));
+    let per_minute = |count: f64| count * 60.0 / bucket_seconds as f64;
+    let instant = |bucket: usize| {
+        DateTime::from_timestamp(start + bucket as i64 * bucket_seconds, 0)
+            .map(|t| format_timestamp(&t))
+            .unwrap_or_default()
+    };
+
+    let mut bursts = Vec::new();
+    let mut bucket = 0;
+    while bucket < total.counts.len() {
+        if !busy(bucket) {
+            bucket += 1;
+            continue;
+        }
+        let first = bucket;
+        while bucket < total.counts.len() && busy(bucket) {
+            bucket += 1;
+        }
+        let window = first..bucket;
+
+        let mut involved: Vec<(&str, u32)> = groups.iter()
+            .filter(|group| window.clone().any(|b| group.is_busy(b)))
+            .map(|group| (group.fingerprint, group.counts[window.clone()].iter().sum()))
+            .collect();
+        // The log spiked without any one group standing out: name the groups above their usual rate
+        if involved.is_empty() {
+            involved = all_groups.iter()
+                .filter(|group| window.clone().any(|b| group.counts[b] as f64 > group.baseline))
+                .map(|group| (group.fingerprint, group.counts[window.clone()].iter().sum()))
+                .collect();
+        }
+        involved.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let peak = total.counts[window.clone()].iter().copied().max().unwrap_or_default();
+        bursts.push(Burst {
+            start: instant(first),
+            end: instant(bucket),
+            peak_rate: per_minute(peak as f64),
+            baseline_rate: per_minute(total.baseline),
+            fingerprints: involved.into_iter().map(|(fingerprint, _)| fingerprint.to_string()).collect(),
+        });
+    }
+
+    bursts
+}
+
+fn series<'a>(fingerprint: &'a str, counts: Vec<u32>, options: &ParserOptions) -> Series<'a> {
+    let baseline = median(&counts);
+    Series {
+        fingerprint,
+        threshold: (options.burst_factor * baseline).max(options.burst_min_count.max(1) as f64),
+        baseline,
+        counts,
+    }
+}
+
+impl Series<'_> {
+    fn is_busy(&self, bucket: usize) -> bool {
+        self.counts[bucket] as f64 >= self.threshold
+    }
+}
+
+/// Median of the counts; empty buckets take part, so sporadic errors have a baseline of 0
+fn median(counts: &[u32]) -> f64 {
+    if counts.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = counts.to_vec();
+    sorted.sort_unstable();
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0
+    } else {
+        sorted[middle] as f64
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<chrono::Utc> {
+        DateTime::from_timestamp(1_792_191_600 + seconds, 0).unwrap()
+    }
+
+    fn minutes(histogram: &mut Histogram, fingerprint: &str, per_minute: &[u32]) {
+        for (minute, count) in per_minute.iter().enumerate() {
+            for _ in 0..*count {
+                histogram.record(fingerprint, Some(&at(minute as i64 * 60)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_spiking_against_its_own_baseline() {
+        let mut histogram = Histogram::new(Some(60), 60);
+        minutes(&mut histogram, "steady", &[10, 12, 9, 11, 10, 10, 12, 11]);
+        minutes(&mut histogram, "spiky", &[1, 0, 1, 0, 9, 14, 0, 1]);
+
+        let bursts = detect(&histogram, &ParserOptions::default());
+        assert_eq!(bursts, vec![Burst {
+            start: "2026-10-16T23:04:00.000Z".to_string(),
+            end: "2026-10-16T23:06:00.000Z".to_string(),
+            peak_rate: 24.0,
+            baseline_rate: 12.0,
+            fingerprints: vec!["spiky".to_string()],
+        }]);
+    }
+
+    #[test]
+    fn test_log_wide_spike() {
+        let mut histogram = Histogram::new(Some(60), 60);
+        for fingerprint in ["a", "b", "c", "d"] {
+            minutes(&mut histogram, fingerprint, &[0, 1, 0, 0, 2, 0, 1, 0]);
+        }
+
+        let bursts = detect(&histogram, &ParserOptions::default());
+        assert_eq!(bursts.len(), 1);
+        assert_eq!(bursts[0].start, "2026-10-16T23:04:00.000Z");
+        assert_eq!(bursts[0].fingerprints, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_median_of_very_busy_buckets() {
+        assert_eq!(median(&[u32::MAX, u32::MAX]), u32::MAX as f64);
+    }
+
+    #[test]
+    fn test_steady_log_has_no_bursts() {
+        let mut histogram = Histogram::new(Some(60), 60);
+        minutes(&mut histogram, "steady", &[10, 12, 9, 11, 10, 10, 12, 11]);
+        assert!(detect(&histogram, &ParserOptions::default()).is_empty());
+    }
+}
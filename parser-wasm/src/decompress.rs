@@ -0,0 +1,460 @@
+use std::io::Write;
+
+use bzip2_rs::decoder::{Decoder as Bzip2Decoder, ReadState, WriteState};
+use flate2::write::MultiGzDecoder;
+use ruzstd::decoding::FrameDecoder;
+use serde::{Serialize, Deserialize};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// "BZh" and the block size digit; '9' stands in for '1'..='9' while sniffing
+const BZIP2_MAGIC: [u8; 4] = *b"BZh9";
+/// Skippable zstd frames have magics 0x184D2A50..=0x184D2A5F (little-endian)
+const ZSTD_SKIPPABLE: [u8; 3] = [0x2a, 0x4d, 0x18];
+/// What follows "BZh" and the block size digit at the start of a bzip2 stream: the magic
+/// of its first block, or the end-of-stream magic when it is empty
+const BZIP2_BLOCK_MAGIC: [u8; 6] = [0x31, 0x41, 0x59, 0x26, 0x53, 0x59];
+const BZIP2_END_MAGIC: [u8; 6] = [0x17, 0x72, 0x45, 0x38, 0x50, 0x90];
+/// More than the bzip2 decoder ever buffers (a 900k block and its header)
+const BZIP2_MAX_BUFFERED: usize = 1 << 20;
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Compression of a byte stream, recognised by its magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+/// Incremental decompressor for byte chunks split at arbitrary points
+/// Uncompressed input passes through unchanged
+pub struct Decompressor {
+    state: State,
+    compression: Option<Compression>,
+}
+
+enum State {
+    /// Holding the first bytes until the magic bytes can be told apart
+    Sniffing(Vec<u8>),
+    Plain,
+    Gzip(Box<MultiGzDecoder<Vec<u8>>>),
+    Zstd(Box<ZstdStream>),
+    Bzip2(Box<Bzip2Stream>),
+    /// The stream was corrupt; everything after the error is dropped
+    Failed,
+}
+
+/// zstd frames, handed to the decoder a whole block at a time: ruzstd's push API can't
+/// resume a block, a frame header or a checksum that is split across chunks
+#[derive(Default)]
+struct ZstdStream {
+    decoder: FrameDecoder,
+    /// Input not yet decoded, starting at a frame or block header
+    pending: Vec<u8>,
+    /// A frame header has been read and its last block hasn't
+    in_frame: bool,
+    /// The current frame ends with a content checksum
+    checksum: bool,
+}
+
+/// bzip2 streams, one after another as pbzip2 and `cat a.bz2 b.bz2` write them
+/// The decoder takes in bytes past the end of a stream without giving them back, so the
+/// last bytes written to it are kept to find where the next stream starts
+#[derive(Default)]
+struct Bzip2Stream {
+    decoder: Bzip2Decoder,
+    /// Bytes written to the decoder, from the start of the stream or the last BZIP2_MAX_BUFFERED
+    written: Vec<u8>,
+    /// `written` no longer holds the start of the stream
+    trimmed: bool,
+    pending: Vec<u8>,
+    /// A stream has ended; `pending` is searched for the next one
+    between_streams: bool,
+}
+
+// ============================================================================
+// DECOMPRESSION
+// ============================================================================
+
+impl Decompressor {
+    pub fn new() -> Decompressor {
+        Decompressor { state: State::Sniffing(Vec::new()), compression: None }
+    }
+
+    /// Compression detected so far; None for plain input or before enough bytes arrived
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Decompress a chunk, appending the output it completes to `out`
+    /// After an error the stream is abandoned and later chunks are ignored
+    pub fn push(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        let result = match &mut self.state {
+            State::Sniffing(head) => {
+                let mut head = std::mem::take(head);
+                head.extend_from_slice(chunk);
+                if is_ambiguous(&head) {
+                    self.state = State::Sniffing(head);
+                    return Ok(());
+                }
+                self.state = detect(&head);
+                self.compression = match self.state {
+                    State::Gzip(_) => Some(Compression::Gzip),
+                    State::Zstd(_) => Some(Compression::Zstd),
+                    State::Bzip2(_) => Some(Compression::Bzip2),
+                    _ => None,
+                };
+                return self.push(&head, out);
+            }
+            State::Plain => {
+                out.extend_from_slice(chunk);
+                Ok(())
+            }
+            State::Gzip(decoder) => decoder.write_all(chunk)
+                .map(|()| out.append(decoder.get_mut()))
+                .map_err(|e| format!("gzip: {}", e)),
+            State::Zstd(stream) => stream.push(chunk, out),
+            State::Bzip2(stream) => stream.push(chunk, out),
+            State::Failed => Ok(()),
+        };
+        self.fail_on_error(result)
+    }
+
+    /// Flush the end of the stream; errors if it was cut short
+    pub fn finish(&mut self, out: &mut Vec<u8>) -> Result<(), String> {
+        let result = match &mut self.state {
+            State::Sniffing(head) => {
+                // Shorter than any magic, so it can only be plain text
+                let head = std::mem::take(head);
+                self.state = State::Plain;
+                out.extend_from_slice(&head);
+                Ok(())
+            }
+            State::Gzip(decoder) => decoder.try_finish()
+                .map(|()| out.append(decoder.get_mut()))
+                .map_err(|e| format!("gzip: {}", e)),
+            State::Zstd(stream) => stream.finish(),
+            State::Bzip2(stream) => stream.finish(out),
+            State::Plain | State::Failed => Ok(()),
+        };
+        self.fail_on_error(result)
+    }
+
+    fn fail_on_error(&mut self, result: Result<(), String>) -> Result<(), String> {
+        if result.is_err() {
+            self.state = State::Failed;
+        }
+        result
+    }
+}
+
+/// Too short to tell: could still become a magic, or turn out to be plain text
+fn is_ambiguous(head: &[u8]) -> bool {
+    let magics: [&[u8]; 3] = [&GZIP_MAGIC, &ZSTD_MAGIC, &BZIP2_MAGIC];
+    magics.iter().any(|magic| head.len() < magic.len() && magic.starts_with(head))
+}
+
+fn detect(head: &[u8]) -> State {
+    if head.starts_with(&GZIP_MAGIC) {
+        State::Gzip(Box::new(MultiGzDecoder::new(Vec::new())))
+    } else if head.starts_with(&ZSTD_MAGIC) {
+        State::Zstd(Box::default())
+    } else if head.starts_with(&BZIP2_MAGIC[..3]) && head.get(3).is_some_and(|b| (b'1'..=b'9').contains(b)) {
+        State::Bzip2(Box::default())
+    } else {
+        State::Plain
+    }
+}
+
+// ============================================================================
+// ZSTD
+// ============================================================================
+
+impl ZstdStream {
+    fn push(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        self.pending.extend_from_slice(chunk);
+        loop {
+            let progressed = if self.in_frame { self.decode_blocks(out)? } else { self.start_frame()? };
+            if !progressed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        if self.in_frame || !self.pending.is_empty() {
+            return Err("zstd: input ends in the middle of a frame".to_string());
+        }
+        Ok(())
+    }
+
+    /// Read a frame header, or skip a skippable frame, once it has fully arrived
+    fn start_frame(&mut self) -> Result<bool, String> {
+        let pending = &self.pending;
+        if pending.len() < 5 {
+            return Ok(false);
+        }
+
+        if pending[1..4] == ZSTD_SKIPPABLE && pending[0] & 0xf0 == 0x50 {
+            let Some(length) = pending.get(4..8) else {
+                return Ok(false);
+            };
+            // A 4 GiB skippable frame doesn't fit in a 32-bit usize
+            let end = usize::try_from(u32::from_le_bytes([length[0], length[1], length[2], length[3]])).ok()
+                .and_then(|length| length.checked_add(8))
+                .ok_or("zstd: skippable frame too large")?;
+            if pending.len() < end {
+                return Ok(false);
+            }
+            self.pending.drain(..end);
+            return Ok(true);
+        }
+        if pending[..4] != ZSTD_MAGIC {
+            return Err("zstd: invalid frame magic".to_string());
+        }
+
+        // Magic, descriptor, window descriptor, dictionary id and content size
+        let descriptor = pending[4];
+        let single_segment = descriptor & 0x20 != 0;
+        let dictionary_id = [0, 1, 2, 4][(descriptor & 0x03) as usize];
+        let content_size = match descriptor >> 6 {
+            0 => usize::from(single_segment),
+            1 => 2,
+            2 => 4,
+            _ => 8,
+        };
+        let header_len = 5 + usize::from(!single_segment) + dictionary_id + content_size;
+        if pending.len() < header_len {
+            return Ok(false);
+        }
+
+        self.decoder.init(&pending[..header_len]).map_err(|e| format!("zstd: {}", e))?;
+        self.checksum = descriptor & 0x04 != 0;
+        self.in_frame = true;
+        self.pending.drain(..header_len);
+        Ok(true)
+    }
+
+    /// Decode the blocks that have fully arrived; the last one only together with its checksum
+    fn decode_blocks(&mut self, out: &mut Vec<u8>) -> Result<bool, String> {
+        let mut end = 0;
+        let mut last = false;
+        while let Some(header) = self.pending.get(end..end + 3) {
+            let header = u32::from_le_bytes([header[0], header[1], header[2], 0]);
+            let is_last = header & 1 == 1;
+            // RLE blocks store one byte however many they expand to; type 3 is reserved
+            let content = match (header >> 1) & 0x03 {
+                1 => 1,
+                3 => return Err("zstd: reserved block type".to_string()),
+                _ => (header >> 3) as usize,
+            };
+            let block_end = end + 3 + content + if is_last && self.checksum { 4 } else { 0 };
+            if block_end > self.pending.len() {
+                break;
+            }
+            end = block_end;
+            if is_last {
+                last = true;
+                break;
+            }
+        }
+        if end == 0 {
+            return Ok(false);
+        }
+
+        let (read, _) = self.decoder.decode_from_to(&self.pending[..end], &mut [])
+            .map_err(|e| format!("zstd: {}", e))?;
+        if read != end {
+            return Err("zstd: corrupt block".to_string());
+        }
+        self.pending.drain(..end);
+        if let Some(data) = self.decoder.collect() {
+            out.extend_from_slice(&data);
+        }
+
+        if last {
+            if let (Some(expected), Some(actual)) = (self.decoder.get_checksum_from_data(), self.decoder.get_calculated_checksum()) {
+                if expected != actual {
+                    return Err("zstd: checksum mismatch".to_string());
+                }
+            }
+            self.in_frame = false;
+        }
+        Ok(true)
+    }
+}
+
+// ============================================================================
+// BZIP2
+// ============================================================================
+
+impl Bzip2Stream {
+    fn push(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        self.pending.extend_from_slice(chunk);
+        self.drain(out, false)
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<(), String> {
+        self.drain(out, true)
+    }
+
+    /// Feed pending input and read out every block it completes
+    /// The decoder can't tell a short last block from a partial one, so that block waits for the end
+    fn drain(&mut self, out: &mut Vec<u8>, at_end: bool) -> Result<(), String> {
+        let mut buf = [0; 8192];
+        // One end signal lets the decoder read the short last block, a second one its footer
+        let mut end_signals = 0;
+
+        loop {
+            if self.between_streams && !self.next_stream(at_end) {
+                return Ok(());
+            }
+            match self.decoder.read(&mut buf).map_err(|e| format!("bzip2: {}", e))? {
+                ReadState::Read(n) => {
+                    out.extend_from_slice(&buf[..n]);
+                    end_signals = 0;
+                }
+                ReadState::Eof => {
+                    // The next stream starts somewhere in what the decoder was given and didn't use;
+                    // skip a byte of this stream's own header so it isn't found again
+                    let mut rest = std::mem::take(&mut self.written);
+                    if !self.trimmed && !rest.is_empty() {
+                        rest.remove(0);
+                    }
+                    rest.append(&mut self.pending);
+                    self.pending = rest;
+                    self.between_streams = true;
+                    end_signals = 0;
+                }
+                ReadState::NeedsWrite(_) if self.pending.is_empty() => {
+                    if !at_end {
+                        return Ok(());
+                    }
+                    if end_signals == 2 {
+                        return Err("bzip2: input ends in the middle of a block".to_string());
+                    }
+                    // An empty write tells the decoder the input has ended
+                    self.decoder.write(&[]).map_err(|e| format!("bzip2: {}", e))?;
+                    end_signals += 1;
+                }
+                ReadState::NeedsWrite(_) => {
+                    if let WriteState::Written(n) = self.decoder.write(&self.pending).map_err(|e| format!("bzip2: {}", e))? {
+                        self.written.extend(self.pending.drain(..n));
+                        if self.written.len() > 2 * BZIP2_MAX_BUFFERED {
+                            self.written.drain(..self.written.len() - BZIP2_MAX_BUFFERED);
+                            self.trimmed = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Start a fresh decoder at the next stream header in `pending`
+    /// False while none has arrived; bytes after the last stream are ignored, as bzip2 does
+    fn next_stream(&mut self, at_end: bool) -> bool {
+        let start = self.pending.windows(10).position(|w| {
+            w.starts_with(b"BZh")
+                && (b'1'..=b'9').contains(&w[3])
+                && (w[4..] == BZIP2_BLOCK_MAGIC || w[4..] == BZIP2_END_MAGIC)
+        });
+        let Some(start) = start else {
+            // Keep the bytes that could begin a header completed by the next chunk
+            let keep = if at_end { 0 } else { self.pending.len().min(9) };
+            self.pending.drain(..self.pending.len() - keep);
+            return false;
+        };
+        self.pending.drain(..start);
+        self.decoder = Bzip2Decoder::new();
+        self.trimmed = false;
+        self.between_streams = false;
+        true
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "2026-10-16 23:05:01 ERROR Disk full\n2026-10-16 23:05:02 ERROR Disk full\n";
+
+    // bzip2 -c of LOG
+    const LOG_BZ2: [u8; 88] = [
+        0x42, 0x5a, 0x68, 0x39, 0x31, 0x41, 0x59, 0x26, 0x53, 0x59, 0x5a, 0x07, 0x26, 0x83, 0x00, 0x00,
+        0x0e, 0x5f, 0x80, 0x00, 0x10, 0x40, 0x02, 0x7b, 0x10, 0x06, 0x00, 0x90, 0x00, 0x01, 0x2c, 0x0a,
+        0x00, 0x20, 0x00, 0x50, 0xa6, 0x13, 0x4d, 0x01, 0xa6, 0x20, 0x55, 0x53, 0x13, 0x46, 0x26, 0x8d,
+        0x34, 0xd3, 0x2a, 0xbc, 0x52, 0x99, 0x6e, 0xaa, 0x4b, 0xa6, 0x82, 0x56, 0x21, 0x55, 0x91, 0x4d,
+        0x07, 0x08, 0xae, 0xba, 0x67, 0x4c, 0x20, 0xc2, 0x4c, 0x3e, 0x7a, 0xd3, 0x4d, 0x3f, 0x17, 0x72,
+        0x45, 0x38, 0x50, 0x90, 0x5a, 0x07, 0x26, 0x83,
+    ];
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zstd(data: &[u8]) -> Vec<u8> {
+        ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest)
+    }
+
+    /// Decompress in chunks of `size` bytes
+    fn decompress(input: &[u8], size: usize) -> (Option<Compression>, Result<Vec<u8>, String>) {
+        let mut decompressor = Decompressor::new();
+        let mut out = Vec::new();
+        let result = input.chunks(size)
+            .try_for_each(|chunk| decompressor.push(chunk, &mut out))
+            .and_then(|()| decompressor.finish(&mut out));
+        (decompressor.compression(), result.map(|()| out))
+    }
+
+    #[test]
+    fn test_formats_are_detected_across_chunk_boundaries() {
+        for size in [1, 3, 7, 4096] {
+            assert_eq!(decompress(&gzip(LOG.as_bytes()), size), (Some(Compression::Gzip), Ok(LOG.as_bytes().to_vec())));
+            assert_eq!(decompress(&zstd(LOG.as_bytes()), size), (Some(Compression::Zstd), Ok(LOG.as_bytes().to_vec())));
+            assert_eq!(decompress(&LOG_BZ2, size), (Some(Compression::Bzip2), Ok(LOG.as_bytes().to_vec())));
+            assert_eq!(decompress(LOG.as_bytes(), size), (None, Ok(LOG.as_bytes().to_vec())));
+        }
+    }
+
+    #[test]
+    fn test_multi_block_and_multi_member_streams() {
+        // Several 128 KiB zstd blocks
+        let large: String = (0..20_000).map(|i| format!("2026-10-16 23:05:01 ERROR request {} failed\n", i)).collect();
+        assert_eq!(decompress(&zstd(large.as_bytes()), 1000).1, Ok(large.as_bytes().to_vec()));
+
+        // Concatenated gzip members, as `cat a.gz b.gz` produces
+        let mut members = gzip(b"first\n");
+        members.extend(gzip(b"second\n"));
+        assert_eq!(decompress(&members, 5).1, Ok(b"first\nsecond\n".to_vec()));
+
+        // Concatenated bzip2 streams, as pbzip2 and `cat a.bz2 b.bz2` produce
+        let streams = [LOG_BZ2.as_slice(), &LOG_BZ2, &LOG_BZ2].concat();
+        for size in [1, 7, 4096] {
+            assert_eq!(decompress(&streams, size).1, Ok(LOG.repeat(3).into_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_short_plain_input() {
+        assert_eq!(decompress(b"BZ", 1), (None, Ok(b"BZ".to_vec())));
+        assert_eq!(decompress(b"BZh is not bzip2", 2), (None, Ok(b"BZh is not bzip2".to_vec())));
+    }
+
+    #[test]
+    fn test_truncated_input_is_an_error() {
+        let compressed = zstd(LOG.as_bytes());
+        assert!(decompress(&compressed[..compressed.len() - 2], 7).1.is_err());
+        assert!(decompress(&LOG_BZ2[..40], 7).1.is_err());
+    }
+}
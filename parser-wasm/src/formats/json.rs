@@ -0,0 +1,159 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use super::{decoded_fraction, DecodedRecord, Level, LogFormat};
+use crate::is_stack_trace_line;
+use crate::options::ParserOptions;
+use crate::traces::node;
+
+/// JSON Lines: one JSON object per line (pino, bunyan, zap, structlog)
+pub struct JsonFormat;
+
+impl LogFormat for JsonFormat {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn detect(&self, sample: &[&str], options: &ParserOptions) -> f32 {
+        decoded_fraction(sample, |line| decode(line, &options.json_fields).is_some())
+    }
+
+    fn starts_record(&self, line: &str, pending: &[String], _options: &ParserOptions) -> bool {
+        // Cheap check - escaped stacks inside the object must not look like continuations
+        line.trim_start().starts_with('{') ||
+            !(is_stack_trace_line(line) || node::continues_record(line, pending))
+    }
+
+    fn decode(&self, line: &str, options: &ParserOptions) -> Option<DecodedRecord> {
+        decode(line, &options.json_fields)
+    }
+}
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Dotted field paths used to read well-known fields from a JSON record
+/// Each field lists candidate paths in priority order - the first one present wins
+/// Defaults cover pino, bunyan, zap, structlog and ECS-style loggers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonFieldPaths {
+    pub level: Vec<String>,
+    pub message: Vec<String>,
+    pub timestamp: Vec<String>,
+    pub error_name: Vec<String>,
+    pub stack: Vec<String>,
+}
+
+impl Default for JsonFieldPaths {
+    fn default() -> Self {
+        fn paths(items: &[&str]) -> Vec<String> {
+            items.iter().map(|s| s.to_string()).collect()
+        }
+
+        JsonFieldPaths {
+            level: paths(&["level", "lvl", "severity", "log.level", "levelname"]),
+            message: paths(&["msg", "message", "event", "@message", "err.message", "error.message", "error"]),
+            timestamp: paths(&["time", "timestamp", "ts", "@timestamp"]),
+            error_name: paths(&["err.type", "err.name", "error.type", "error.name", "error.kind"]),
+            stack: paths(&["err.stack", "error.stack", "error.stack_trace", "stack", "stack_trace", "stacktrace", "exception"]),
+        }
+    }
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a single JSON Lines record
+/// Returns None when the line is not a JSON object
+pub fn decode(line: &str, paths: &JsonFieldPaths) -> Option<DecodedRecord> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('{') || !trimmed.ends_with('}') {
+        return None;
+    }
+
+    let value: Value = serde_json::from_str(trimmed).ok()?;
+    if !value.is_object() {
+        return None;
+    }
+
+    let level = paths.level.iter()
+        .filter_map(|path| lookup(&value, path))
+        .find_map(|v| match v {
+            Value::Number(n) => n.as_i64().and_then(Level::from_number),
+            Value::String(s) => Level::from_name(s),
+            _ => None,
+        });
+
+    Some(DecodedRecord {
+        level,
+        message: first_string(&value, &paths.message),
+        timestamp: first_string(&value, &paths.timestamp),
+        error_name: first_string(&value, &paths.error_name),
+        stack: first_string(&value, &paths.stack),
+        ..DecodedRecord::default()
+    })
+}
+
+/// Resolve a dotted path ("err.stack") against a JSON value
+/// A literal key containing dots ("log.level") takes precedence over nesting
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if let Some(v) = value.get(path) {
+        return Some(v);
+    }
+    path.split('.').try_fold(value, |v, key| v.get(key))
+}
+
+/// Return the first candidate path holding a non-empty scalar value
+fn first_string(value: &Value, paths: &[String]) -> Option<String> {
+    paths.iter()
+        .filter_map(|path| lookup(value, path))
+        .find_map(|v| match v {
+            Value::String(s) if !s.trim().is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pino_record() {
+        let line = r#"{"level":50,"time":1760655901000,"msg":"db timeout","err":{"type":"Error","message":"db timeout","stack":"Error: db timeout\n    at query (/app/src/db.js:42:13)"}}"#;
+        let record = decode(line, &JsonFieldPaths::default()).unwrap();
+
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.message.as_deref(), Some("db timeout"));
+        assert_eq!(record.timestamp.as_deref(), Some("1760655901000"));
+        assert_eq!(record.error_name.as_deref(), Some("Error"));
+        assert!(record.stack.unwrap().contains("db.js:42:13"));
+    }
+
+    #[test]
+    fn test_decode_custom_paths() {
+        let paths = JsonFieldPaths {
+            level: vec!["meta.sev".to_string()],
+            message: vec!["text".to_string()],
+            ..JsonFieldPaths::default()
+        };
+        let record = decode(r#"{"meta":{"sev":"FATAL"},"text":"out of memory"}"#, &paths).unwrap();
+
+        assert_eq!(record.level, Some(Level::Fatal));
+        assert_eq!(record.message.as_deref(), Some("out of memory"));
+    }
+
+    #[test]
+    fn test_non_json_lines_are_ignored() {
+        let paths = JsonFieldPaths::default();
+        assert!(decode("2025-01-01 12:00:00 ERROR {not json}", &paths).is_none());
+        assert!(decode("{broken", &paths).is_none());
+    }
+}
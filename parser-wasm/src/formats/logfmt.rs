@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use super::{decoded_fraction, DecodedRecord, Level, LogFormat};
+use crate::options::ParserOptions;
+
+// Well-known keys, in priority order
+// "at" and "desc" are the level and message keys used by the Heroku router
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity", "at"];
+const MESSAGE_KEYS: &[&str] = &["msg", "message", "err", "error", "desc"];
+const TIMESTAMP_KEYS: &[&str] = &["time", "ts", "timestamp", "t"];
+const STACK_KEYS: &[&str] = &["stack", "stacktrace"];
+
+/// logfmt: space-separated key=value pairs (Go, Heroku)
+pub struct LogfmtFormat;
+
+impl LogFormat for LogfmtFormat {
+    fn name(&self) -> &'static str {
+        "logfmt"
+    }
+
+    fn detect(&self, sample: &[&str], _options: &ParserOptions) -> f32 {
+        decoded_fraction(sample, |line| decode(line).is_some())
+    }
+
+    fn decode(&self, line: &str, _options: &ParserOptions) -> Option<DecodedRecord> {
+        decode(line)
+    }
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a single logfmt record (`level=error msg="db timeout" user_id=123`)
+/// Returns None unless the whole line is key=value pairs and names a level or message
+pub fn decode(line: &str) -> Option<DecodedRecord> {
+    let pairs = tokenize(line)?;
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let mut fields: BTreeMap<String, String> = pairs.into_iter().collect();
+
+    let level_key = LEVEL_KEYS.iter()
+        .find(|key| fields.get(**key).and_then(|v| Level::from_name(v)).is_some());
+    let has_message = MESSAGE_KEYS.iter().any(|key| fields.contains_key(*key));
+    if level_key.is_none() && !has_message {
+        return None;
+    }
+
+    let level = level_key
+        .and_then(|key| fields.remove(*key))
+        .and_then(|v| Level::from_name(&v));
+
+    Some(DecodedRecord {
+        level,
+        message: take_first(&mut fields, MESSAGE_KEYS),
+        timestamp: take_first(&mut fields, TIMESTAMP_KEYS),
+        stack: take_first(&mut fields, STACK_KEYS),
+        attributes: fields,
+        ..DecodedRecord::default()
+    })
+}
+
+/// Remove and return the first non-empty value among `keys`
+fn take_first(fields: &mut BTreeMap<String, String>, keys: &[&str]) -> Option<String> {
+    let key = keys.iter().find(|key| fields.get(**key).is_some_and(|v| !v.is_empty()))?;
+    fields.remove(*key)
+}
+
+/// Split a line into key/value pairs, unescaping quoted values
+/// Any token that is not `key=value` (or a bare key) rejects the whole line
+fn tokenize(line: &str) -> Option<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut bare_keys = 0;
+    let mut chars = line.trim().chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+            if !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '/' | '@')) {
+                return None;
+            }
+            key.push(c);
+        }
+        if key.is_empty() {
+            return None;
+        }
+
+        // Bare keys are boolean flags in logfmt
+        if chars.next_if_eq(&'=').is_none() {
+            pairs.push((key, "true".to_string()));
+            bare_keys += 1;
+            continue;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => match chars.next()? {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'r' => value.push('\r'),
+                        other => value.push(other),
+                    },
+                    c => value.push(c),
+                }
+            }
+            // A closing quote must end the token
+            if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                return None;
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+
+        pairs.push((key, value));
+    }
+
+    // Require real key=value pairs to outnumber bare keys, so prose is not mistaken for logfmt
+    if bare_keys * 2 > pairs.len() {
+        return None;
+    }
+
+    Some(pairs)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_logfmt_record() {
+        let record = decode(r#"level=error msg="db timeout" user_id=123 duration=3.2s"#).unwrap();
+
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.message.as_deref(), Some("db timeout"));
+        assert_eq!(record.attributes.get("user_id").map(String::as_str), Some("123"));
+        assert_eq!(record.attributes.get("duration").map(String::as_str), Some("3.2s"));
+        assert!(!record.attributes.contains_key("level"));
+    }
+
+    #[test]
+    fn test_quoted_values_and_escapes() {
+        let record = decode(r#"lvl=warn err="bad \"quote\" here\nnext" path="/a b""#).unwrap();
+
+        assert_eq!(record.level, Some(Level::Warn));
+        assert_eq!(record.message.as_deref(), Some("bad \"quote\" here\nnext"));
+        assert_eq!(record.attributes.get("path").map(String::as_str), Some("/a b"));
+    }
+
+    #[test]
+    fn test_heroku_router_line() {
+        let record = decode(r#"at=error code=H12 desc="Request timeout" method=GET path="/""#).unwrap();
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.attributes.get("code").map(String::as_str), Some("H12"));
+    }
+
+    #[test]
+    fn test_plain_text_is_not_logfmt() {
+        assert!(decode("2025-05-27 00:40:12 ERROR something failed").is_none());
+        assert!(decode("Connection failed for user=5").is_none());
+        assert!(decode(r#"level=error msg="unterminated"#).is_none());
+    }
+}
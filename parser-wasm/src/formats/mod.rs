@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::options::ParserOptions;
+use crate::traces::node;
+use crate::{is_stack_trace_line, ErrorType};
+
+// Built-in formats
+pub mod json;
+pub mod logfmt;
+pub mod syslog;
+pub mod text;
+
+// ============================================================================
+// FORMAT TRAIT & REGISTRY
+// ============================================================================
+
+/// A log format the parser can detect and decode
+pub trait LogFormat: Sync {
+    /// Stable identifier, also used to force a format from JS ("json", "logfmt", ...)
+    fn name(&self) -> &'static str;
+
+    /// Confidence (0.0 - 1.0) that the sample lines are written in this format
+    fn detect(&self, sample: &[&str], options: &ParserOptions) -> f32;
+
+    /// Whether a line begins a new record (used by the "auto" framing rule)
+    /// `pending` holds the lines of the record framed so far
+    /// Default: every decodable line starts a record; raw stack trace lines printed
+    /// after a structured line continue it
+    fn starts_record(&self, line: &str, pending: &[String], options: &ParserOptions) -> bool {
+        self.decode(line, options).is_some() ||
+            !(is_stack_trace_line(line) || node::continues_record(line, pending))
+    }
+
+    /// Decode one record into fields
+    /// None means the line is handled by the plain-text pipeline
+    fn decode(&self, line: &str, options: &ParserOptions) -> Option<DecodedRecord>;
+}
+
+/// Built-in formats in detection priority order (ties go to the earlier entry)
+/// Plain text is last: it is the fallback when nothing structured matches
+static FORMATS: &[&dyn LogFormat] = &[
+    &json::JsonFormat,
+    &logfmt::LogfmtFormat,
+    &syslog::SyslogFormat,
+    &text::TextFormat,
+];
+
+/// Look up a built-in format by name
+pub fn find_format(name: &str) -> Option<&'static dyn LogFormat> {
+    FORMATS.iter().copied().find(|f| f.name().eq_ignore_ascii_case(name.trim()))
+}
+
+/// Pick the format that best matches the sample lines
+pub fn detect_format(sample: &[&str], options: &ParserOptions) -> (&'static dyn LogFormat, f32) {
+    let sample: Vec<&str> = sample.iter().copied().filter(|l| !l.trim().is_empty()).collect();
+
+    let mut best: (&'static dyn LogFormat, f32) = (&text::TextFormat, 0.0);
+    for format in FORMATS {
+        let confidence = format.detect(&record_starts(*format, &sample, options), options);
+        if confidence > best.1 {
+            best = (*format, confidence);
+        }
+    }
+    best
+}
+
+/// First lines of the records the sample frames into under the format's own rule
+/// Stack trace lines printed raw after a record continue it, so they don't count against the format
+fn record_starts<'a>(format: &dyn LogFormat, sample: &[&'a str], options: &ParserOptions) -> Vec<&'a str> {
+    let mut starts = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+    for line in sample {
+        if pending.is_empty() || format.starts_record(line, &pending, options) {
+            starts.push(*line);
+            pending.clear();
+        }
+        pending.push(line.to_string());
+    }
+    starts
+}
+
+/// Fraction of sample lines accepted by `decode`
+/// Shared `detect` implementation for line-oriented structured formats
+fn decoded_fraction(sample: &[&str], decode: impl Fn(&str) -> bool) -> f32 {
+    if sample.is_empty() {
+        return 0.0;
+    }
+    sample.iter().filter(|line| decode(line)).count() as f32 / sample.len() as f32
+}
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Normalised log level shared by all structured decoders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+/// Fields decoded from a single structured log record
+#[derive(Debug, Clone, Default)]
+pub struct DecodedRecord {
+    pub level: Option<Level>,
+    pub message: Option<String>,
+    pub timestamp: Option<String>,
+    pub error_name: Option<String>,
+    pub stack: Option<String>,
+    /// Source fields from syslog headers
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub pid: Option<String>,
+    /// Remaining key/value pairs not mapped to a well-known field
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Format chosen for a parse, reported in ParseResult
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatDetection {
+    pub name: String,
+    pub confidence: f32,
+    /// True when the caller forced the format instead of auto-detection
+    pub forced: bool,
+}
+
+// ============================================================================
+// LEVEL MAPPING
+// ============================================================================
+
+impl Level {
+    /// Parse a textual level name ("error", "WARN", "fatal", ...)
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name.trim().to_lowercase().as_str() {
+            "trace" | "finest" | "finer" => Some(Level::Trace),
+            "debug" | "fine" | "verbose" => Some(Level::Debug),
+            "info" | "information" | "notice" | "config" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            "error" | "err" | "exception" | "severe" => Some(Level::Error),
+            "fatal" | "critical" | "crit" | "panic" | "dpanic" | "emerg" | "emergency" | "alert" => {
+                Some(Level::Fatal)
+            }
+            other => other.parse::<i64>().ok().and_then(Level::from_number),
+        }
+    }
+
+    /// Map pino / bunyan numeric levels (10 = trace ... 60 = fatal)
+    pub fn from_number(level: i64) -> Option<Level> {
+        match level {
+            l if l >= 60 => Some(Level::Fatal),
+            l if l >= 50 => Some(Level::Error),
+            l if l >= 40 => Some(Level::Warn),
+            l if l >= 30 => Some(Level::Info),
+            l if l >= 20 => Some(Level::Debug),
+            l if l >= 10 => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    /// Error type used for counting and display
+    pub fn error_type(self) -> ErrorType {
+        match self {
+            Level::Fatal | Level::Error => ErrorType::Error,
+            Level::Warn => ErrorType::Warning,
+            Level::Info | Level::Debug | Level::Trace => ErrorType::Info,
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_names_and_numbers() {
+        assert_eq!(Level::from_name("ERROR"), Some(Level::Error));
+        assert_eq!(Level::from_name("warning"), Some(Level::Warn));
+        assert_eq!(Level::from_name("50"), Some(Level::Error));
+        assert_eq!(Level::from_number(60), Some(Level::Fatal));
+        assert_eq!(Level::from_number(30), Some(Level::Info));
+        assert_eq!(Level::from_name("chatty"), None);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let options = ParserOptions::default();
+
+        let json = [r#"{"level":30,"msg":"a"}"#, r#"{"level":50,"msg":"b"}"#, "    at x (/a.js:1:2)"];
+        let (format, confidence) = detect_format(&json, &options);
+        assert_eq!(format.name(), "json");
+        assert!(confidence > 0.6);
+
+        let logfmt = [r#"level=info msg="a" id=1"#, r#"level=error msg="b" id=2"#];
+        assert_eq!(detect_format(&logfmt, &options).0.name(), "logfmt");
+
+        let syslog = ["Oct 16 23:05:01 web01 nginx[123]: upstream timed out"];
+        assert_eq!(detect_format(&syslog, &options).0.name(), "syslog");
+
+        // The frame continues the first record, so it takes two plain records to outweigh the logfmt one
+        let text = ["2025-05-27 00:40:12 ERROR boom", "    at x (/a.js:1:2)", "2025-05-27 00:40:13 INFO done", "level=info msg=x"];
+        assert_eq!(detect_format(&text, &options).0.name(), "text");
+    }
+
+    #[test]
+    fn test_detect_json_with_raw_java_traces() {
+        let json = [
+            r#"{"level":"info","msg":"starting"}"#,
+            r#"{"level":"error","msg":"request failed"}"#,
+            "java.lang.IllegalStateException: pool exhausted",
+            "\tat com.acme.db.Pool.acquire(Pool.java:88)",
+            "\tat com.acme.api.Handler.handle(Handler.java:31)",
+            "Caused by: java.net.SocketTimeoutException: connect timed out",
+            "\tat java.base/java.net.Socket.connect(Socket.java:633)",
+            "\t... 12 more",
+            r#"{"level":"info","msg":"recovered"}"#,
+        ];
+        assert_eq!(detect_format(&json, &ParserOptions::default()).0.name(), "json");
+    }
+
+    #[test]
+    fn test_find_format() {
+        assert_eq!(find_format("JSON").map(|f| f.name()), Some("json"));
+        assert!(find_format("xml").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{decoded_fraction, DecodedRecord, Level, LogFormat};
+use crate::options::ParserOptions;
+
+lazy_static! {
+    // RFC 5424: <PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]
+    static ref RFC5424_HEADER: Regex = Regex::new(
+        r"^<(\d{1,3})>(\d{1,2}) (\S+) (\S+) (\S+) (\S+) (\S+) "
+    ).unwrap();
+
+    // RFC 3164 (BSD): [<PRI>]Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG
+    // High-precision ISO timestamps (rsyslog) are only accepted with a PRI, so plain
+    // "2025-01-01T10:00:00Z ERROR Foo: bar" application lines are not mistaken for syslog
+    static ref RFC3164: Regex = Regex::new(
+        r"^(?:<(\d{1,3})>([A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\S+)|([A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2})) (\S+) ([^\s\[:]+)(?:\[(\d+)\])?: ?(.*)$"
+    ).unwrap();
+}
+
+/// Facility names indexed by facility code (RFC 5424 section 6.2.1)
+const FACILITIES: [&str; 24] = [
+    "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news",
+    "uucp", "cron", "authpriv", "ftp", "ntp", "security", "console", "solaris-cron",
+    "local0", "local1", "local2", "local3", "local4", "local5", "local6", "local7",
+];
+
+/// Syslog: RFC 5424 and RFC 3164 (BSD) lines
+pub struct SyslogFormat;
+
+impl LogFormat for SyslogFormat {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    fn detect(&self, sample: &[&str], _options: &ParserOptions) -> f32 {
+        decoded_fraction(sample, |line| decode(line).is_some())
+    }
+
+    fn decode(&self, line: &str, _options: &ParserOptions) -> Option<DecodedRecord> {
+        decode(line)
+    }
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode an RFC 5424 or RFC 3164 syslog line
+/// The PRI facility is kept as an attribute, the PRI severity becomes the record level
+pub fn decode(line: &str) -> Option<DecodedRecord> {
+    decode_rfc5424(line).or_else(|| decode_rfc3164(line))
+}
+
+fn decode_rfc5424(line: &str) -> Option<DecodedRecord> {
+    let caps = RFC5424_HEADER.captures(line)?;
+    let pri = caps[1].parse::<u8>().ok()?;
+    let rest = &line[caps.get(0)?.end()..];
+
+    let (structured_data, message) = split_structured_data(rest)?;
+
+    let mut record = record_from_pri(Some(pri), message);
+    record.timestamp = nil_to_none(&caps[3]);
+    record.hostname = nil_to_none(&caps[4]);
+    record.app_name = nil_to_none(&caps[5]);
+    record.pid = nil_to_none(&caps[6]);
+    if let Some(msg_id) = nil_to_none(&caps[7]) {
+        record.attributes.insert("msgid".to_string(), msg_id);
+    }
+    record.attributes.extend(structured_data);
+
+    Some(record)
+}
+
+fn decode_rfc3164(line: &str) -> Option<DecodedRecord> {
+    let caps = RFC3164.captures(line)?;
+    let pri = caps.get(1).and_then(|m| m.as_str().parse::<u8>().ok());
+    let timestamp = caps.get(2).or_else(|| caps.get(3)).map(|m| m.as_str().to_string());
+
+    let mut record = record_from_pri(pri, &caps[7]);
+    record.timestamp = timestamp;
+    record.hostname = Some(caps[4].to_string());
+    record.app_name = Some(caps[5].to_string());
+    record.pid = caps.get(6).map(|m| m.as_str().to_string());
+
+    Some(record)
+}
+
+/// Build the common part of a record from the PRI value and message body
+fn record_from_pri(pri: Option<u8>, message: &str) -> DecodedRecord {
+    let mut record = DecodedRecord {
+        message: Some(message.trim().to_string()).filter(|m| !m.is_empty()),
+        ..DecodedRecord::default()
+    };
+
+    if let Some(pri) = pri {
+        let facility = (pri / 8) as usize;
+        if let Some(name) = FACILITIES.get(facility) {
+            record.attributes.insert("facility".to_string(), name.to_string());
+        }
+        record.level = Some(level_from_severity(pri % 8));
+    }
+
+    record
+}
+
+/// Map a syslog severity (0 = emerg ... 7 = debug) onto a level
+fn level_from_severity(severity: u8) -> Level {
+    match severity {
+        0..=2 => Level::Fatal,
+        3 => Level::Error,
+        4 => Level::Warn,
+        5 | 6 => Level::Info,
+        _ => Level::Debug,
+    }
+}
+
+/// Split RFC 5424 STRUCTURED-DATA from the message
+/// `[id name="value"]` params become "id.name" attributes
+fn split_structured_data(rest: &str) -> Option<(BTreeMap<String, String>, &str)> {
+    let mut attributes = BTreeMap::new();
+
+    if let Some(message) = rest.strip_prefix('-') {
+        return Some((attributes, message));
+    }
+
+    let bytes = rest.as_bytes();
+    let mut pos = 0;
+    while bytes.get(pos) == Some(&b'[') {
+        let end = find_element_end(rest, pos)?;
+        let element = &rest[pos + 1..end];
+        let (id, params) = element.split_once(' ').unwrap_or((element, ""));
+
+        for (name, value) in parse_sd_params(params) {
+            attributes.insert(format!("{}.{}", id, name), value);
+        }
+        pos = end + 1;
+    }
+
+    if pos == 0 {
+        return None;
+    }
+    Some((attributes, &rest[pos..]))
+}
+
+/// Find the closing bracket of an SD-ELEMENT, skipping brackets inside quoted values
+fn find_element_end(rest: &str, start: usize) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in rest[start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ']' if !in_quotes => return Some(start + i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse `name="value"` pairs inside an SD-ELEMENT
+fn parse_sd_params(params: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut chars = params.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=') {
+            name.push(c);
+        }
+        if name.is_empty() || chars.next() != Some('=') || chars.next() != Some('"') {
+            break;
+        }
+
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => value.extend(chars.next()),
+                '"' => break,
+                c => value.push(c),
+            }
+        }
+        result.push((name, value));
+    }
+
+    result
+}
+
+/// RFC 5424 uses "-" for missing header fields
+fn nil_to_none(value: &str) -> Option<String> {
+    if value == "-" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_rfc5424() {
+        let line = r#"<11>1 2026-10-16T23:05:01Z host app 1234 ID47 [exampleSDID@32473 iut="3" eventSource="App]lication"] db connection lost"#;
+        let record = decode(line).unwrap();
+
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.timestamp.as_deref(), Some("2026-10-16T23:05:01Z"));
+        assert_eq!(record.hostname.as_deref(), Some("host"));
+        assert_eq!(record.app_name.as_deref(), Some("app"));
+        assert_eq!(record.pid.as_deref(), Some("1234"));
+        assert_eq!(record.message.as_deref(), Some("db connection lost"));
+        assert_eq!(record.attributes.get("facility").map(String::as_str), Some("user"));
+        assert_eq!(record.attributes.get("msgid").map(String::as_str), Some("ID47"));
+        assert_eq!(
+            record.attributes.get("exampleSDID@32473.eventSource").map(String::as_str),
+            Some("App]lication")
+        );
+    }
+
+    #[test]
+    fn test_decode_rfc5424_nil_fields() {
+        let record = decode("<165>1 2026-10-16T23:05:01Z - - - - - started").unwrap();
+        assert_eq!(record.level, Some(Level::Info));
+        assert_eq!(record.hostname, None);
+        assert_eq!(record.message.as_deref(), Some("started"));
+    }
+
+    #[test]
+    fn test_decode_rfc3164() {
+        let record = decode("Oct 16 23:05:01 web01 nginx[123]: upstream timed out").unwrap();
+
+        assert_eq!(record.level, None);
+        assert_eq!(record.timestamp.as_deref(), Some("Oct 16 23:05:01"));
+        assert_eq!(record.hostname.as_deref(), Some("web01"));
+        assert_eq!(record.app_name.as_deref(), Some("nginx"));
+        assert_eq!(record.pid.as_deref(), Some("123"));
+        assert_eq!(record.message.as_deref(), Some("upstream timed out"));
+
+        let record = decode("<3>Oct  6 23:05:01 web01 kernel: Out of memory").unwrap();
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.attributes.get("facility").map(String::as_str), Some("kern"));
+    }
+
+    #[test]
+    fn test_application_lines_are_not_syslog() {
+        assert!(decode("2025-05-27T00:40:12Z ERROR TypeError: x is undefined").is_none());
+    }
+}
@@ -0,0 +1,37 @@
+use super::{DecodedRecord, LogFormat};
+use crate::is_stack_trace_line;
+use crate::traces::{go, native, node, php, python, ruby, rust};
+use crate::options::ParserOptions;
+
+/// Plain-text logs, handled by the regex pipeline in lib.rs
+/// Always a plausible match, so it wins only when no structured format does better
+pub struct TextFormat;
+
+impl LogFormat for TextFormat {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn detect(&self, _sample: &[&str], _options: &ParserOptions) -> f32 {
+        0.5
+    }
+
+    fn starts_record(&self, line: &str, pending: &[String], _options: &ParserOptions) -> bool {
+        // Python prints frames before the exception, Go dumps have unindented function
+        // lines and blank lines, Rust prints the panic payload unindented and Rails
+        // and PHP print unindented backtraces, sanitizer reports mix all of these, and
+        // Node closes its [cause] block with a bare brace
+        !python::continues_record(line, pending) &&
+            !go::continues_record(line, pending) &&
+            !rust::continues_record(line, pending) &&
+            !ruby::continues_record(line, pending) &&
+            !php::continues_record(line, pending) &&
+            !native::continues_record(line, pending) &&
+            !node::continues_record(line, pending) &&
+            !is_stack_trace_line(line)
+    }
+
+    fn decode(&self, _line: &str, _options: &ParserOptions) -> Option<DecodedRecord> {
+        None
+    }
+}
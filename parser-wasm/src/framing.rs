@@ -0,0 +1,198 @@
+use serde::{Serialize, Deserialize};
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use crate::formats::LogFormat;
+use crate::options::ParserOptions;
+
+lazy_static! {
+    // Timestamps that commonly open a log record:
+    // ISO 8601 / RFC 3339, syslog "Oct 16 23:05:01", Apache "[16/Oct/2026:23:05:01", bare "23:05:01"
+    static ref TIMESTAMP_PREFIX: Regex = Regex::new(
+        r"^[\[(]?(?:\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}|[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}|\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}|\d{2}:\d{2}:\d{2})"
+    ).unwrap();
+}
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// How physical lines are grouped into logical records
+/// From JS: `{ mode: "auto" }`, `{ mode: "timestamp_prefix" }` or
+/// `{ mode: "start_pattern", pattern: "^\\[\\d+" }`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FramingRule {
+    /// Use the log format's own rule (stack trace heuristics for plain text)
+    #[default]
+    Auto,
+    /// A line starting with a timestamp begins a new record (like Filebeat multiline)
+    TimestampPrefix,
+    /// A line matching `pattern` begins a new record; all other lines continue it
+    StartPattern { pattern: String },
+}
+
+/// Compiled form of a FramingRule
+enum StartRule {
+    Auto,
+    TimestampPrefix,
+    Pattern(Regex),
+}
+
+/// Groups physical lines into logical records
+/// The record being built is kept across `push` calls, so lines can arrive one at a time
+pub struct RecordFramer {
+    rule: StartRule,
+    max_lines: usize,
+    pending: Vec<String>,
+}
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+impl RecordFramer {
+    /// Build a framer from the parser options
+    /// An invalid start pattern is returned as an error message
+    pub fn new(options: &ParserOptions) -> Result<RecordFramer, String> {
+        let rule = match &options.framing {
+            FramingRule::Auto => StartRule::Auto,
+            FramingRule::TimestampPrefix => StartRule::TimestampPrefix,
+            FramingRule::StartPattern { pattern } => StartRule::Pattern(
+                Regex::new(pattern).map_err(|e| format!("Invalid record start pattern '{}': {}", pattern, e))?,
+            ),
+        };
+
+        Ok(RecordFramer {
+            rule,
+            max_lines: options.max_record_lines.max(1),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Framer that uses the format's own rule
+    pub fn auto(options: &ParserOptions) -> RecordFramer {
+        RecordFramer {
+            rule: StartRule::Auto,
+            max_lines: options.max_record_lines.max(1),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Add a line; returns the previous record once this line starts a new one
+    pub fn push(&mut self, line: &str, format: &dyn LogFormat, options: &ParserOptions) -> Option<Vec<String>> {
+        let starts_record = self.pending.is_empty() ||
+                            self.pending.len() >= self.max_lines ||
+                            self.starts_record(line, format, options);
+
+        let completed = if starts_record && !self.pending.is_empty() {
+            Some(std::mem::take(&mut self.pending))
+        } else {
+            None
+        };
+
+        self.pending.push(line.to_string());
+        completed
+    }
+
+    /// Return the record still being built (end of input)
+    pub fn flush(&mut self) -> Option<Vec<String>> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.pending))
+        }
+    }
+
+    fn starts_record(&self, line: &str, format: &dyn LogFormat, options: &ParserOptions) -> bool {
+        match &self.rule {
+            StartRule::Auto => format.starts_record(line, &self.pending, options),
+            StartRule::TimestampPrefix => TIMESTAMP_PREFIX.is_match(line),
+            StartRule::Pattern(regex) => regex.is_match(line),
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::text::TextFormat;
+
+    fn frame(lines: &[&str], options: &ParserOptions) -> Vec<Vec<String>> {
+        let mut framer = RecordFramer::new(options).unwrap();
+        let mut records: Vec<Vec<String>> = lines.iter()
+            .filter_map(|line| framer.push(line, &TextFormat, options))
+            .collect();
+        records.extend(framer.flush());
+        records
+    }
+
+    #[test]
+    fn test_timestamp_prefix_keeps_indented_sql_together() {
+        let options = ParserOptions {
+            framing: FramingRule::TimestampPrefix,
+            ..ParserOptions::default()
+        };
+        let records = frame(&[
+            "2026-10-16 23:05:01 ERROR query failed:",
+            "    SELECT *",
+            "    FROM users",
+            "2026-10-16 23:05:02 INFO next",
+        ], &options);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].len(), 3);
+    }
+
+    #[test]
+    fn test_auto_keeps_frames_but_not_indented_sql() {
+        let records = frame(&[
+            "2026-10-16 23:05:01 ERROR TypeError: x is not a function",
+            "    at render (/app/src/view.js:10:5)",
+            "2026-10-16 23:05:02 ERROR query failed:",
+            "    SELECT *",
+            "    FROM users",
+            "2026-10-16 23:05:03 INFO next",
+        ], &ParserOptions::default());
+
+        assert_eq!(records[0].len(), 2);
+        assert_eq!(records[1], vec!["2026-10-16 23:05:02 ERROR query failed:"]);
+        assert_eq!(records.len(), 5);
+    }
+
+    #[test]
+    fn test_start_pattern() {
+        let options = ParserOptions {
+            framing: FramingRule::StartPattern { pattern: r"^\[\d+\]".to_string() },
+            ..ParserOptions::default()
+        };
+        let records = frame(&["[1] first", "detail", "[2] second"], &options);
+
+        assert_eq!(records, vec![vec!["[1] first", "detail"], vec!["[2] second"]]);
+    }
+
+    #[test]
+    fn test_max_lines_splits_runaway_records() {
+        let options = ParserOptions {
+            framing: FramingRule::StartPattern { pattern: "^START".to_string() },
+            max_record_lines: 2,
+            ..ParserOptions::default()
+        };
+        let records = frame(&["START", "a", "b", "c"], &options);
+
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let options = ParserOptions {
+            framing: FramingRule::StartPattern { pattern: "(".to_string() },
+            ..ParserOptions::default()
+        };
+        assert!(RecordFramer::new(&options).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::timestamps::format_timestamp;
+
+/// Automatic bucket widths in seconds, each a multiple of the one before so
+/// buckets can be merged in place; past a week the width keeps doubling, as does
+/// a configured width when the span outgrows the bucket limit
+const BUCKET_SIZES: [i64; 14] = [
+    1, 5, 10, 30, 60, 300, 600, 1800, 3600, 3 * 3600, 6 * 3600, 12 * 3600, 86400, 7 * 86400,
+];
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Occurrence counts over time, for the whole log and per error group
+/// Every series shares the same buckets, so they line up in a chart
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeHistogram {
+    /// Width of each bucket
+    pub bucket_seconds: u64,
+    /// Start of the first bucket; None when no occurrence had a timestamp
+    pub start: Option<String>,
+    /// Occurrences per bucket across all error groups
+    pub total: Vec<u32>,
+    /// Occurrences per bucket of each reported error, by fingerprint
+    pub by_fingerprint: BTreeMap<String, Vec<u32>>,
+    /// Occurrences without a timestamp, which no bucket can hold
+    pub untimed: usize,
+}
+
+/// Sparse counts, maintained as occurrences are recorded
+#[derive(Debug)]
+pub struct Histogram {
+    bucket_seconds: i64,
+    /// Widen through BUCKET_SIZES; false when the width is configured, which only ever doubles
+    automatic: bool,
+    max_buckets: usize,
+    /// Counts keyed by bucket start (epoch seconds, a multiple of the width)
+    total: BTreeMap<i64, u32>,
+    by_fingerprint: HashMap<String, BTreeMap<i64, u32>>,
+    untimed: usize,
+}
+
+// ============================================================================
+// COUNTING
+// ============================================================================
+
+impl Histogram {
+    /// Configured bucket width, or None to start at one second and widen as needed
+    /// Either way the width grows when the span would need more than `max_buckets`, so a
+    /// stray timestamp years away from the rest can't make the series huge
+    pub fn new(bucket_seconds: Option<u64>, max_buckets: usize) -> Histogram {
+        let fixed = bucket_seconds.filter(|seconds| *seconds > 0);
+        Histogram {
+            bucket_seconds: fixed.map_or(BUCKET_SIZES[0], |seconds| seconds as i64),
+            automatic: fixed.is_none(),
+            max_buckets: max_buckets.max(1),
+            total: BTreeMap::new(),
+            by_fingerprint: HashMap::new(),
+            untimed: 0,
+        }
+    }
+
+    /// Count one occurrence of an error group
+    pub fn record(&mut self, fingerprint: &str, timestamp: Option<&DateTime<Utc>>) {
+        let Some(timestamp) = timestamp else {
+            self.untimed += 1;
+            return;
+        };
+
+        let bucket = timestamp.timestamp().div_euclid(self.bucket_seconds) * self.bucket_seconds;
+        *self.total.entry(bucket).or_default() += 1;
+        if let Some(counts) = self.by_fingerprint.get_mut(fingerprint) {
+            *counts.entry(bucket).or_default() += 1;
+        } else {
+            self.by_fingerprint.insert(fingerprint.to_string(), BTreeMap::from([(bucket, 1)]));
+        }
+
+        while self.span() > self.max_buckets {
+            self.widen();
+        }
+    }
+
+    /// Dense series from the first bucket to the last, for the given fingerprints
+    pub fn build<'a>(&self, fingerprints: impl IntoIterator<Item = &'a str>) -> TimeHistogram {
+        TimeHistogram {
+            bucket_seconds: self.bucket_seconds as u64,
+            start: self.start().and_then(|start| DateTime::from_timestamp(start, 0)).map(|start| format_timestamp(&start)),
+            total: self.total_series(),
+            by_fingerprint: fingerprints.into_iter()
+                .filter_map(|fingerprint| Some((fingerprint.to_string(), self.series(fingerprint)?)))
+                .collect(),
+            untimed: self.untimed,
+        }
+    }
+
+    pub fn bucket_seconds(&self) -> i64 {
+        self.bucket_seconds
+    }
+
+    /// Start of the first bucket, in epoch seconds
+    pub fn start(&self) -> Option<i64> {
+        self.total.keys().next().copied()
+    }
+
+    pub fn fingerprints(&self) -> impl Iterator<Item = &str> {
+        self.by_fingerprint.keys().map(String::as_str)
+    }
+
+    /// Occurrences per bucket across all error groups
+    pub fn total_series(&self) -> Vec<u32> {
+        self.dense(&self.total)
+    }
+
+    /// Occurrences per bucket of one error group, on the same buckets as the total
+    pub fn series(&self, fingerprint: &str) -> Option<Vec<u32>> {
+        self.by_fingerprint.get(fingerprint).map(|counts| self.dense(counts))
+    }
+
+    fn dense(&self, counts: &BTreeMap<i64, u32>) -> Vec<u32> {
+        let mut series = vec![0; self.span()];
+        if let Some(start) = self.start() {
+            for (bucket, count) in counts {
+                series[((bucket - start) / self.bucket_seconds) as usize] = *count;
+            }
+        }
+        series
+    }
+
+    /// Buckets from the first occurrence to the last, empty ones included
+    fn span(&self) -> usize {
+        match (self.total.keys().next(), self.total.keys().next_back()) {
+            (Some(first), Some(last)) => ((last - first) / self.bucket_seconds) as usize + 1,
+            _ => 0,
+        }
+    }
+
+    /// Move to the next automatic width and merge the buckets that now share a start
+    fn widen(&mut self) {
+        let next = BUCKET_SIZES.iter().copied().find(|size| *size > self.bucket_seconds);
+        self.bucket_seconds = match next {
+            Some(size) if self.automatic => size,
+            _ => self.bucket_seconds * 2,
+        };
+
+        let size = self.bucket_seconds;
+        let merge = |counts: &BTreeMap<i64, u32>| {
+            let mut merged = BTreeMap::new();
+            for (bucket, count) in counts {
+                *merged.entry(bucket.div_euclid(size) * size).or_default() += count;
+            }
+            merged
+        };
+        self.total = merge(&self.total);
+        for counts in self.by_fingerprint.values_mut() {
+            *counts = merge(counts);
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_792_191_600 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn test_fixed_buckets() {
+        let mut histogram = Histogram::new(Some(60), 10);
+        for (fingerprint, seconds) in [("a", 0), ("a", 59), ("b", 61), ("a", 300)] {
+            histogram.record(fingerprint, Some(&at(seconds)));
+        }
+        histogram.record("a", None);
+
+        let result = histogram.build(["a", "b"]);
+        assert_eq!(result.bucket_seconds, 60);
+        assert_eq!(result.start.as_deref(), Some("2026-10-16T23:00:00.000Z"));
+        assert_eq!(result.total, vec![2, 1, 0, 0, 0, 1]);
+        assert_eq!(result.by_fingerprint["a"], vec![2, 0, 0, 0, 0, 1]);
+        assert_eq!(result.by_fingerprint["b"], vec![0, 1, 0, 0, 0, 0]);
+        assert_eq!(result.untimed, 1);
+    }
+
+    #[test]
+    fn test_fixed_buckets_widen_past_the_limit() {
+        let mut histogram = Histogram::new(Some(60), 10);
+        histogram.record("a", Some(&at(0)));
+        histogram.record("a", Some(&at(120)));
+        // A clock reset to the epoch
+        histogram.record("b", Some(&DateTime::from_timestamp(0, 0).unwrap()));
+
+        let result = histogram.build(["a", "b"]);
+        assert!(result.total.len() <= 10);
+        assert_eq!(result.bucket_seconds % 60, 0);
+        assert_eq!(result.total.iter().sum::<u32>(), 3);
+        assert_eq!(result.by_fingerprint["a"].last(), Some(&2));
+    }
+
+    #[test]
+    fn test_automatic_buckets_widen_as_the_span_grows() {
+        let mut histogram = Histogram::new(None, 10);
+        histogram.record("a", Some(&at(0)));
+        histogram.record("a", Some(&at(8)));
+        assert_eq!(histogram.build(["a"]).bucket_seconds, 1);
+
+        // Two hours no longer fit in ten 10-minute buckets
+        histogram.record("b", Some(&at(2 * 3600)));
+        let result = histogram.build(["a", "b"]);
+        assert_eq!(result.bucket_seconds, 1800);
+        assert_eq!(result.total, vec![2, 0, 0, 0, 1]);
+        assert_eq!(result.by_fingerprint["b"], vec![0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_only_requested_fingerprints_are_built() {
+        let mut histogram = Histogram::new(None, 60);
+        histogram.record("a", Some(&at(0)));
+        histogram.record("b", Some(&at(0)));
+        assert_eq!(histogram.build(["a"]).by_fingerprint.keys().collect::<Vec<_>>(), vec!["a"]);
+    }
+}
@@ -2,11 +2,34 @@ use wasm_bindgen::prelude::*;
 use serde::{Serialize, Deserialize};
 use regex::Regex;
 use lazy_static::lazy_static;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 // Pattern learning module
 mod pattern_learning;
 
+// Structured log formats, record framing, stack trace decoders, histograms, byte input and parser options
+mod bursts;
+mod decompress;
+mod formats;
+mod framing;
+mod histogram;
+mod lines;
+mod options;
+mod sanitize;
+mod timestamps;
+mod traces;
+
+use bursts::Burst;
+use decompress::{Compression, Decompressor};
+use formats::{DecodedRecord, FormatDetection, LogFormat};
+use framing::RecordFramer;
+use histogram::{Histogram, TimeHistogram};
+use lines::LineDecoder;
+use options::ParserOptions;
+use timestamps::{InstantRange, TimestampParser};
+use traces::{DecodedException, ErrorCause, StackFrame};
+
 // ============================================================================
 // TYPES & STRUCTS
 // ============================================================================
@@ -34,6 +57,8 @@ pub struct ParsedError {
     #[serde(rename = "type")]
     pub error_type: ErrorType,
     pub severity: Severity,
+    /// Exception class when the log names one ("ValueError", "java.io.IOException")
+    pub exception_type: Option<String>,
     pub message: String,
     pub template: String,              // NEW: Normalized message with variable placeholders
     pub variables: Vec<Variable>,      // NEW: List of extracted variables
@@ -42,14 +67,40 @@ pub struct ParsedError {
     pub line: Option<u32>,
     pub column: Option<u32>,
     pub occurrences: u32,
+    /// First occurrence, RFC 3339 in UTC with millisecond precision ("2026-10-16T23:05:01.120Z")
     pub timestamp: Option<String>,
+    /// Earliest and latest occurrence timestamps (logs aren't always in order)
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    /// Timestamps of the most recent occurrences, oldest first (bounded by max_occurrence_timestamps)
+    pub occurrence_timestamps: Vec<String>,
     pub fingerprint: String,
+    /// Stack frames of the first occurrence, innermost first
+    pub frames: Vec<StackFrame>,
+    /// Exception chain, from the outer exception's direct cause down to the root cause
+    pub causes: Vec<ErrorCause>,
+    /// Source host, application and process id (from syslog headers)
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub pid: Option<String>,
+    /// Extra key/value fields from structured records (logfmt, syslog), first occurrence
+    pub attributes: BTreeMap<String, String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ParseResult {
     pub summary: LogStats,
     pub errors: Vec<ParsedError>,
+    /// Log format used for this parse (detected or forced)
+    pub format: FormatDetection,
+    /// Occurrences over time, overall and for each reported error
+    pub histogram: TimeHistogram,
+    /// Windows where errors arrived well above their usual rate, oldest first
+    pub bursts: Vec<Burst>,
+    /// Compression of the bytes given to process_chunk; None for plain text
+    pub compression: Option<Compression>,
+    /// Why decompression stopped early; the input after that point wasn't analysed
+    pub decompression_error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -59,6 +110,10 @@ pub struct LogStats {
     pub total_warnings: usize,
     pub total_info: usize,
     pub unique_errors: usize,
+    /// Lines left out by the time_range option
+    pub out_of_range_lines: usize,
+    /// Bytes given to process_chunk that weren't valid UTF-8 / UTF-16, replaced by U+FFFD
+    pub invalid_bytes: usize,
 }
 
 /// Types of variables that can be extracted from log messages
@@ -90,7 +145,7 @@ pub struct CustomPattern {
 // Thread-local storage for custom patterns
 use std::cell::RefCell;
 thread_local! {
-    static CUSTOM_PATTERNS: RefCell<Vec<CustomPattern>> = RefCell::new(Vec::new());
+    static CUSTOM_PATTERNS: RefCell<Vec<CustomPattern>> = const { RefCell::new(Vec::new()) };
 }
 
 // ============================================================================
@@ -152,23 +207,20 @@ lazy_static! {
         r"(?i)\b(INFO|DEBUG|TRACE)\b"
     ).unwrap();
 
-    // Timestamp patterns (ISO 8601, common formats)
-    static ref TIMESTAMP: Regex = Regex::new(
-        r"(\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}(?:\.\d{3})?(?:Z|[+-]\d{2}:?\d{2})?)"
-    ).unwrap();
-
     // Additional stack trace patterns
+    // \t/app/server.go:42 +0x1c (function lines are recognised inside Go dumps only)
     static ref GO_STACK: Regex = Regex::new(
-        r"^\s*([^\s]+)\(([^)]*)\)\s*$|^\s+([^:]+):(\d+)\s+[+0-9a-fx]+"
+        r"^\s+(\S+\.go):(\d+)(?:\s+\+0x[0-9a-f]+)?\s*$"
     ).unwrap();
 
+    // thread 'main' panicked at src/main.rs:10:5: / thread 'main' panicked at 'msg', src/main.rs:10:5
     static ref RUST_PANIC: Regex = Regex::new(
-        r"at\s+([^\(]+)\s+\(([^:]+):(\d+):(\d+)\)"
+        r"panicked at (?:'.*', )?([^\s:]+):(\d+)(?::(\d+))?"
     ).unwrap();
 
-    // Caused by / Suppressed patterns (for chained errors)
+    // Caused by / Suppressed / Node [cause] patterns (for chained errors)
     static ref CAUSED_BY: Regex = Regex::new(
-        r"(?i)^\s*(?:Caused by|Suppressed):\s*(.+)"
+        r"(?i)^\s*(?:Caused by|Suppressed|\[cause\]):\s*(.+)"
     ).unwrap();
 
     // Context lines (code snippets in stack traces)
@@ -207,6 +259,30 @@ lazy_static! {
 // HELPER FUNCTIONS
 // ============================================================================
 
+/// Log a debug message to the browser console
+/// No-op outside wasm so the parser can run (and be tested) natively
+fn debug_log(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
+
+/// Report a problem to the browser console
+/// No-op outside wasm, like debug_log
+pub(crate) fn console_error(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::error_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
+
+/// Serialize a result for JS
+/// Maps become plain objects and `None` becomes `null`, matching the TS types in wasm-parser.ts
+fn to_js<T: Serialize>(value: &T) -> JsValue {
+    value.serialize(&serde_wasm_bindgen::Serializer::json_compatible()).unwrap()
+}
+
 /// Generate fingerprint for error deduplication using blake3
 /// Uses the template (with variable placeholders) instead of raw message
 /// This ensures errors with the same pattern are grouped together
@@ -222,13 +298,6 @@ fn generate_fingerprint(template: &str, file: &Option<String>, line: &Option<u32
     hash.to_hex().to_string()
 }
 
-/// Extract timestamp from log line
-fn extract_timestamp(line: &str) -> Option<String> {
-    TIMESTAMP.captures(line)
-        .and_then(|cap| cap.get(1))
-        .map(|m| m.as_str().to_string())
-}
-
 /// Extract template and variables from a message
 /// Returns (template, variables) where template has placeholders like {UUID}, {IP}, {ID}
 /// Priority: Custom patterns → Universal patterns
@@ -239,22 +308,22 @@ fn extract_template(message: &str) -> (String, Vec<Variable>) {
     // Priority 1: Try custom patterns first (user-taught patterns)
     let custom_match = CUSTOM_PATTERNS.with(|patterns| {
         let patterns = patterns.borrow();
-        web_sys::console::log_1(&format!("Checking {} custom patterns for message: {}", patterns.len(), message).into());
+        debug_log(&format!("Checking {} custom patterns for message: {}", patterns.len(), message));
 
         for pattern in patterns.iter() {
-            web_sys::console::log_1(&format!("  Trying regex: {}", pattern.regex).into());
-            web_sys::console::log_1(&format!("  Template: {}", pattern.template).into());
+            debug_log(&format!("  Trying regex: {}", pattern.regex));
+            debug_log(&format!("  Template: {}", pattern.template));
 
             if let Ok(regex) = Regex::new(&pattern.regex) {
                 if regex.is_match(message) {
                     // Found a match! Use this template
-                    web_sys::console::log_1(&format!("  ✅ MATCH! Using template: {}", pattern.template).into());
+                    debug_log(&format!("  ✅ MATCH! Using template: {}", pattern.template));
                     return Some(pattern.template.clone());
                 } else {
-                    web_sys::console::log_1(&"  ❌ No match".into());
+                    debug_log("  ❌ No match");
                 }
             } else {
-                web_sys::console::log_1(&"  ⚠️ Invalid regex".into());
+                debug_log("  ⚠️ Invalid regex");
             }
         }
         None
@@ -417,28 +486,41 @@ fn extract_java_location(stack_line: &str) -> (Option<String>, Option<u32>, Opti
 /// Extract file location from Go stack trace
 fn extract_go_location(stack_line: &str) -> (Option<String>, Option<u32>, Option<u32>) {
     if let Some(caps) = GO_STACK.captures(stack_line) {
-        // Go stack traces have two formats:
-        // 1. "file.go:45 +0x123"
-        // 2. "package/path"
-        if let Some(file) = caps.get(3) {
-            let line = caps.get(4).and_then(|m| m.as_str().parse::<u32>().ok());
-            return (Some(file.as_str().to_string()), line, None);
-        }
+        let line = caps.get(2).and_then(|m| m.as_str().parse::<u32>().ok());
+        return (Some(caps[1].to_string()), line, None);
     }
     (None, None, None)
 }
 
+/// File location of a frame parsed by one of the trace decoders
+fn frame_location(frame: Option<StackFrame>) -> (Option<String>, Option<u32>, Option<u32>) {
+    match frame {
+        Some(frame) if frame.file.is_some() => (frame.file, frame.line, frame.column),
+        _ => (None, None, None),
+    }
+}
+
 /// Extract file location from Rust panic trace
 fn extract_rust_location(stack_line: &str) -> (Option<String>, Option<u32>, Option<u32>) {
     if let Some(caps) = RUST_PANIC.captures(stack_line) {
-        let file = caps.get(2).map(|m| m.as_str().to_string());
-        let line = caps.get(3).and_then(|m| m.as_str().parse::<u32>().ok());
-        let column = caps.get(4).and_then(|m| m.as_str().parse::<u32>().ok());
+        let file = caps.get(1).map(|m| m.as_str().to_string());
+        let line = caps.get(2).and_then(|m| m.as_str().parse::<u32>().ok());
+        let column = caps.get(3).and_then(|m| m.as_str().parse::<u32>().ok());
         return (file, line, column);
     }
     (None, None, None)
 }
 
+/// Check if a line opens an error (exception header or ERROR-like keyword)
+fn is_error_line(line: &str) -> bool {
+    is_exception_line(line) || GENERIC_ERROR.is_match(line)
+}
+
+/// Check if a line is a language exception header (Node, Python, Java)
+fn is_exception_line(line: &str) -> bool {
+    NODE_ERROR.is_match(line) || PYTHON_ERROR.is_match(line) || JAVA_ERROR.is_match(line)
+}
+
 /// Check if a line is part of a stack trace
 fn is_stack_trace_line(line: &str) -> bool {
     let trimmed = line.trim();
@@ -463,8 +545,13 @@ fn is_stack_trace_line(line: &str) -> bool {
         return true;
     }
 
-    // Rust panic traces
-    if RUST_PANIC.is_match(line) {
+    // Rust backtrace frames
+    if traces::rust::is_backtrace_frame(line) {
+        return true;
+    }
+
+    // Native frames from gdb / libunwind, which are printed unindented
+    if traces::native::is_frame_line(line) {
         return true;
     }
 
@@ -473,13 +560,23 @@ fn is_stack_trace_line(line: &str) -> bool {
         return true;
     }
 
+    // Elided JVM frames ("... 12 more")
+    if trimmed.starts_with("... ") && trimmed.ends_with(" more") {
+        return true;
+    }
+
+    // .NET inner exceptions and stack trace boundaries
+    if trimmed.starts_with("---> ") || trimmed.starts_with("--- End of ") {
+        return true;
+    }
+
     // Code context lines
     if CODE_CONTEXT.is_match(line) {
         return true;
     }
 
-    // Indented lines (likely part of stack trace)
-    if trimmed.len() > 0 && line.starts_with("    ") && !trimmed.starts_with("//") {
+    // Indented frames; other indented lines (SQL, YAML, pretty-printed JSON) start records of their own
+    if line.starts_with("    ") && traces::parse_frame(line).is_some() {
         return true;
     }
 
@@ -518,11 +615,40 @@ fn extract_location_any_format(line: &str) -> (Option<String>, Option<u32>, Opti
         return (file, line_num, column);
     }
 
+    // Try .NET
+    let (file, line_num, column) = frame_location(traces::dotnet::parse_frame(line));
+    if file.is_some() {
+        return (file, line_num, column);
+    }
+
+    // Try Ruby
+    let (file, line_num, column) = frame_location(traces::ruby::parse_frame(line));
+    if file.is_some() {
+        return (file, line_num, column);
+    }
+
+    // Try PHP
+    let (file, line_num, column) = frame_location(traces::php::location(line));
+    if file.is_some() {
+        return (file, line_num, column);
+    }
+
+    // Try native frames
+    let (file, line_num, column) = frame_location(traces::native::parse_frame(line));
+    if file.is_some() {
+        return (file, line_num, column);
+    }
+
     (None, None, None)
 }
 
 /// Extract error message from different formats
 fn extract_error_message(line: &str) -> String {
+    // Try Rails format (checked first: the message itself may contain "Error:")
+    if let Some(message) = traces::ruby::rails_message(line.trim()) {
+        return message.to_string();
+    }
+
     // Try Node.js format
     if let Some(caps) = NODE_ERROR.captures(line) {
         if let Some(msg) = caps.get(2) {
@@ -559,147 +685,33 @@ fn extract_error_message(line: &str) -> String {
 // MAIN PARSING LOGIC
 // ============================================================================
 
-/// Parse log content and extract errors with deduplication
-fn parse_log_content(content: &str) -> ParseResult {
-    let lines: Vec<&str> = content.lines().collect();
-    let total_lines = lines.len();
-
-    let mut error_map: HashMap<String, ParsedError> = HashMap::new();
-    let mut current_stack_trace = String::new();
-    let mut in_stack_trace = false;
-    let mut last_error_fingerprint: Option<String> = None;
-
-    let mut total_errors = 0;
-    let mut total_warnings = 0;
-    let mut total_info = 0;
-
-    for (_idx, line) in lines.iter().enumerate() {
-        let error_type = determine_error_type(line);
-
-        // Check if this is an error line
-        let is_error_line = NODE_ERROR.is_match(line) ||
-                           PYTHON_ERROR.is_match(line) ||
-                           JAVA_ERROR.is_match(line) ||
-                           GENERIC_ERROR.is_match(line);
-
-        if is_error_line {
-            // Extract error details
-            let message = extract_error_message(line);
-            let timestamp = extract_timestamp(line);
-
-            // Try to extract location from this line using any format
-            let (file, line_num, column) = extract_location_any_format(line);
-
-            // Extract template and variables from message
-            let (template, variables) = extract_template(&message);
-
-            let severity = determine_severity(&error_type, &message);
-            // Use template for fingerprinting to group similar errors
-            let fingerprint = generate_fingerprint(&template, &file, &line_num);
-
-            // Update counts
-            match error_type {
-                ErrorType::Error => total_errors += 1,
-                ErrorType::Warning => total_warnings += 1,
-                ErrorType::Info => total_info += 1,
-            }
-
-            // Check if we've seen this error before
-            if let Some(existing) = error_map.get_mut(&fingerprint) {
-                // Increment occurrence count
-                existing.occurrences += 1;
-                // Add variables from this occurrence
-                existing.variables.extend(variables);
-                // Update full trace with new occurrence
-                existing.full_trace.push_str("\n\n---\n\n");
-                existing.full_trace.push_str(line);
-            } else {
-                // New error - create entry
-                let parsed_error = ParsedError {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    error_type: error_type.clone(),
-                    severity,
-                    message: message.clone(),
-                    template: template.clone(),
-                    variables,
-                    full_trace: line.to_string(),
-                    file: file.clone(),
-                    line: line_num,
-                    column,
-                    occurrences: 1,
-                    timestamp,
-                    fingerprint: fingerprint.clone(),
-                };
-
-                error_map.insert(fingerprint.clone(), parsed_error);
-            }
-
-            last_error_fingerprint = Some(fingerprint);
-            current_stack_trace = String::new();
-            in_stack_trace = true;
-        } else if in_stack_trace && is_stack_trace_line(line) {
-            // This is a stack trace line - append to current error's trace
-            current_stack_trace.push('\n');
-            current_stack_trace.push_str(line);
-
-            if let Some(ref fp) = last_error_fingerprint {
-                if let Some(error) = error_map.get_mut(fp) {
-                    error.full_trace.push('\n');
-                    error.full_trace.push_str(line);
-
-                    // Try to extract location if we don't have one yet
-                    // This is important for multi-line stack traces where the error message
-                    // doesn't contain the location, but the stack trace does
-                    if error.file.is_none() {
-                        let (file, line_num, column) = extract_location_any_format(line);
-                        if file.is_some() {
-                            error.file = file;
-                            error.line = line_num;
-                            error.column = column;
-                        }
-                    }
-                }
-            }
-        } else if in_stack_trace && CAUSED_BY.is_match(line) {
-            // Handle chained errors (Caused by:, Suppressed:)
-            // These are still part of the same error trace
-            if let Some(ref fp) = last_error_fingerprint {
-                if let Some(error) = error_map.get_mut(fp) {
-                    error.full_trace.push('\n');
-                    error.full_trace.push_str(line);
-                }
-            }
-            // Continue in stack trace mode for the chained error
-        } else if !line.trim().is_empty() {
-            // Non-empty line that's not a stack trace - might be a warning or info
-            if GENERIC_WARN.is_match(line) {
-                total_warnings += 1;
-            } else if GENERIC_INFO.is_match(line) {
-                total_info += 1;
-            }
-            in_stack_trace = false;
-        }
-    }
-
-    // Convert to Vec and sort by occurrences (descending)
-    let mut errors: Vec<ParsedError> = error_map.into_values().collect();
-    errors.sort_by(|a, b| b.occurrences.cmp(&a.occurrences));
-
-    let unique_errors = errors.len();
-
-    // Return top 20 only
-    errors.truncate(20);
+/// A single error occurrence, ready to be fingerprinted and grouped
+struct ErrorEvent {
+    error_type: ErrorType,
+    severity: Severity,
+    exception_type: Option<String>,
+    message: String,
+    trace: String,
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    frames: Vec<StackFrame>,
+    causes: Vec<ErrorCause>,
+    frame_signature: Option<String>,
+    hostname: Option<String>,
+    app_name: Option<String>,
+    pid: Option<String>,
+    attributes: BTreeMap<String, String>,
+}
 
-    ParseResult {
-        summary: LogStats {
-            total_lines,
-            total_errors,
-            total_warnings,
-            total_info,
-            unique_errors,
-        },
-        errors,
+/// Parse log content and extract errors with deduplication
+fn parse_log_content(content: &str, options: ParserOptions) -> ParseResult {
+    let mut parser = LogParser::with_options(options);
+    for line in content.lines() {
+        parser.process_line(line);
     }
+    parser.build_result()
 }
 
 // ============================================================================
@@ -710,33 +722,55 @@ fn parse_log_content(content: &str) -> ParseResult {
 /// This allows processing files larger than available memory
 #[wasm_bindgen]
 pub struct LogParser {
+    options: ParserOptions,
+    // Format in use; None while the first lines are being sampled for detection
+    format: Option<&'static dyn LogFormat>,
+    format_detection: Option<FormatDetection>,
+    sample: Vec<String>,
+
+    // Byte input (process_chunk): decompression, then line splitting and text decoding
+    decompressor: Decompressor,
+    // First decompression error; later input is dropped, so the result is incomplete
+    decompression_error: Option<String>,
+    line_decoder: LineDecoder,
+    // Groups lines into multi-line records; holds the partial record between calls
+    framer: RecordFramer,
+    // Lines as logged, for the lines the framer holds; None where stripping changed nothing
+    originals: VecDeque<Option<String>>,
+    // Open Rails requests, so Ruby errors can be linked to the request that raised them
+    rails_requests: traces::ruby::RailsRequests,
+    // Maps for minified JavaScript bundles, applied to frames as errors are recorded
+    source_maps: traces::sourcemap::SourceMaps,
+    // Parses record timestamps, with the default timezone and year from the options
+    timestamps: TimestampParser,
+    // Records outside this range are skipped; None analyses everything
+    time_range: Option<InstantRange>,
+    // Timestamp of the last record that had one, for records that don't
+    last_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    // ProGuard / R8 mappings, applied to records before they are decoded
+    proguard: traces::proguard::ProguardMapping,
+    // Occurrence counts per time bucket, overall and per fingerprint
+    histogram: Histogram,
+    // The same counts on finer buckets, for burst detection
+    burst_histogram: Histogram,
+
     error_map: HashMap<String, ParsedError>,
+    // Most recent occurrence timestamps per fingerprint, copied into the results at the end
+    occurrence_timestamps: HashMap<String, VecDeque<String>>,
     total_lines: usize,
     total_errors: usize,
     total_warnings: usize,
     total_info: usize,
-
-    // State for multi-line stack traces
-    in_stack_trace: bool,
-    last_error_fingerprint: Option<String>,
-    current_stack_trace: String,
+    out_of_range_lines: usize,
 }
 
 #[wasm_bindgen]
 impl LogParser {
     /// Create a new parser instance
+    /// `options` is an optional ParserOptions object (see options.rs)
     #[wasm_bindgen(constructor)]
-    pub fn new() -> LogParser {
-        LogParser {
-            error_map: HashMap::new(),
-            total_lines: 0,
-            total_errors: 0,
-            total_warnings: 0,
-            total_info: 0,
-            in_stack_trace: false,
-            last_error_fingerprint: None,
-            current_stack_trace: String::new(),
-        }
+    pub fn new(options: Option<JsValue>) -> LogParser {
+        LogParser::with_options(ParserOptions::from_js(options))
     }
 
     /// Process a single line of log content
@@ -745,134 +779,638 @@ impl LogParser {
     pub fn process_line(&mut self, line: &str) {
         self.total_lines += 1;
 
-        let error_type = determine_error_type(line);
-
-        // Check if this is an error line
-        let is_error_line = NODE_ERROR.is_match(line) ||
-                           PYTHON_ERROR.is_match(line) ||
-                           JAVA_ERROR.is_match(line) ||
-                           GENERIC_ERROR.is_match(line);
+        // Hold the first lines back until we know which format we're reading
+        if self.format.is_none() {
+            self.sample.push(line.to_string());
+            if self.sample.len() >= self.options.detect_lines {
+                self.select_format();
+            }
+            return;
+        }
 
-        if is_error_line {
-            // Extract error details
-            let message = extract_error_message(line);
-            let timestamp = extract_timestamp(line);
+        self.frame_line(line);
+    }
 
-            // Try to extract location from this line using any format
-            let (file, line_num, column) = extract_location_any_format(line);
+    /// Process a chunk of raw file bytes
+    /// gzip, zstd and bzip2 input is detected from its first bytes and decompressed as it
+    /// arrives; chunks may end anywhere, even inside a character. Call `finish` after the last one
+    /// Text is UTF-8, or UTF-16 when it starts with a byte order mark; lines end at "\n",
+    /// "\r\n" or a lone "\r", and invalid bytes are replaced and counted in LogStats.invalid_bytes
+    #[wasm_bindgen]
+    pub fn process_chunk(&mut self, chunk: &[u8]) {
+        let mut bytes = Vec::new();
+        if let Err(e) = self.decompressor.push(chunk, &mut bytes) {
+            console_error(&format!("Stopped reading compressed input: {}", e));
+            self.decompression_error = Some(e);
+        }
+        for line in self.line_decoder.push(&bytes) {
+            self.process_line(&line);
+        }
+    }
 
-            // Extract template and variables from message
-            let (template, variables) = extract_template(&message);
+    /// End the byte input: flush the decompressor and the last line if it has no newline
+    #[wasm_bindgen]
+    pub fn finish(&mut self) {
+        let mut bytes = Vec::new();
+        if let Err(e) = self.decompressor.finish(&mut bytes) {
+            console_error(&format!("Compressed input is incomplete: {}", e));
+            self.decompression_error = Some(e);
+        }
+        let lines = self.line_decoder.push(&bytes);
+        for line in lines.into_iter().chain(self.line_decoder.finish()) {
+            self.process_line(&line);
+        }
+    }
 
-            let severity = determine_severity(&error_type, &message);
-            // Use template for fingerprinting to group similar errors
-            let fingerprint = generate_fingerprint(&template, &file, &line_num);
+    /// Get the final parse results
+    /// Call this after all lines have been processed
+    #[wasm_bindgen]
+    pub fn get_result(&mut self) -> JsValue {
+        to_js(&self.build_result())
+    }
 
-            // Update counts
-            match error_type {
-                ErrorType::Error => self.total_errors += 1,
-                ErrorType::Warning => self.total_warnings += 1,
-                ErrorType::Info => self.total_info += 1,
+    /// Register a source map for a minified JavaScript bundle
+    /// `name` is the map's file name ("main.3f9a.js.map"), used when the map has no "file" field
+    /// Add maps before processing lines - errors already recorded are not rewritten
+    #[wasm_bindgen]
+    pub fn add_source_map(&mut self, name: &str, bytes: &[u8]) -> bool {
+        match self.source_maps.add(name, bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                console_error(&format!("Skipping source map '{}': {}", name, e));
+                false
             }
+        }
+    }
 
-            // Check if we've seen this error before
-            if let Some(existing) = self.error_map.get_mut(&fingerprint) {
-                // Increment occurrence count
-                existing.occurrences += 1;
-                // Add variables from this occurrence
-                existing.variables.extend(variables);
-                // Update full trace with new occurrence
-                existing.full_trace.push_str("\n\n---\n\n");
-                existing.full_trace.push_str(line);
-            } else {
-                // New error - create entry
-                let parsed_error = ParsedError {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    error_type: error_type.clone(),
-                    severity,
-                    message: message.clone(),
-                    template: template.clone(),
-                    variables,
-                    full_trace: line.to_string(),
-                    file: file.clone(),
-                    line: line_num,
-                    column,
-                    occurrences: 1,
-                    timestamp,
-                    fingerprint: fingerprint.clone(),
-                };
-
-                self.error_map.insert(fingerprint.clone(), parsed_error);
+    /// Register a ProGuard / R8 `mapping.txt` for obfuscated JVM stack traces
+    /// Add mappings before processing lines, like source maps
+    #[wasm_bindgen]
+    pub fn add_proguard_mapping(&mut self, bytes: &[u8]) -> bool {
+        match self.proguard.add(bytes) {
+            Ok(_) => true,
+            Err(e) => {
+                console_error(&format!("Skipping ProGuard mapping: {}", e));
+                false
             }
+        }
+    }
+}
 
-            self.last_error_fingerprint = Some(fingerprint);
-            self.current_stack_trace = String::new();
-            self.in_stack_trace = true;
-        } else if self.in_stack_trace && is_stack_trace_line(line) {
-            // This is a stack trace line - append to current error's trace
-            self.current_stack_trace.push('\n');
-            self.current_stack_trace.push_str(line);
-
-            if let Some(ref fp) = self.last_error_fingerprint {
-                if let Some(error) = self.error_map.get_mut(fp) {
-                    error.full_trace.push('\n');
-                    error.full_trace.push_str(line);
-
-                    // Try to extract location if we don't have one yet
-                    if error.file.is_none() {
-                        let (file, line_num, column) = extract_location_any_format(line);
-                        if file.is_some() {
-                            error.file = file;
-                            error.line = line_num;
-                            error.column = column;
-                        }
-                    }
-                }
-            }
-        } else if self.in_stack_trace && CAUSED_BY.is_match(line) {
-            // Handle chained errors (Caused by:, Suppressed:)
-            if let Some(ref fp) = self.last_error_fingerprint {
-                if let Some(error) = self.error_map.get_mut(fp) {
-                    error.full_trace.push('\n');
-                    error.full_trace.push_str(line);
-                }
-            }
-        } else if !line.trim().is_empty() {
-            // Non-empty line that's not a stack trace - might be a warning or info
-            if GENERIC_WARN.is_match(line) {
-                self.total_warnings += 1;
-            } else if GENERIC_INFO.is_match(line) {
-                self.total_info += 1;
+impl LogParser {
+    /// Create a parser with explicit options (Rust-side constructor)
+    pub fn with_options(options: ParserOptions) -> LogParser {
+        let forced = options.format.as_deref().and_then(|name| {
+            let format = formats::find_format(name);
+            if format.is_none() {
+                console_error(&format!("Unknown log format '{}', falling back to auto-detection", name));
             }
-            self.in_stack_trace = false;
+            format
+        });
+
+        let framer = RecordFramer::new(&options).unwrap_or_else(|e| {
+            console_error(&format!("{}, falling back to automatic framing", e));
+            RecordFramer::auto(&options)
+        });
+
+        let timestamps = TimestampParser::new(&options).unwrap_or_else(|e| {
+            console_error(&format!("{}, using UTC", e));
+            TimestampParser::new(&ParserOptions { default_timezone: None, ..options.clone() }).unwrap()
+        });
+
+        let time_range = options.time_range.as_ref().and_then(|range| {
+            timestamps.range(range)
+                .map_err(|e| console_error(&format!("{}, analysing all lines", e)))
+                .ok()
+        });
+
+        LogParser {
+            format: forced,
+            format_detection: forced.map(|f| FormatDetection {
+                name: f.name().to_string(),
+                confidence: 1.0,
+                forced: true,
+            }),
+            sample: Vec::new(),
+            decompressor: Decompressor::new(),
+            decompression_error: None,
+            line_decoder: LineDecoder::default(),
+            framer,
+            originals: VecDeque::new(),
+            rails_requests: traces::ruby::RailsRequests::default(),
+            source_maps: traces::sourcemap::SourceMaps::default(),
+            timestamps,
+            time_range,
+            last_timestamp: None,
+            proguard: traces::proguard::ProguardMapping::default(),
+            histogram: Histogram::new(options.histogram_bucket_seconds, options.histogram_max_buckets),
+            burst_histogram: Histogram::new(None, options.burst_max_buckets),
+            options,
+            error_map: HashMap::new(),
+            occurrence_timestamps: HashMap::new(),
+            total_lines: 0,
+            total_errors: 0,
+            total_warnings: 0,
+            total_info: 0,
+            out_of_range_lines: 0,
         }
     }
 
-    /// Get the final parse results
-    /// Call this after all lines have been processed
-    #[wasm_bindgen]
-    pub fn get_result(&self) -> JsValue {
+    /// Register every `*.map` file in a directory (native builds)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_source_map_dir(&mut self, dir: &std::path::Path) -> std::io::Result<usize> {
+        self.source_maps.add_dir(dir)
+    }
+
+    /// Build the aggregated result from everything processed so far
+    /// Flushes the record still being framed, so call it once the input has ended
+    pub fn build_result(&mut self) -> ParseResult {
+        // Short logs may end before the detection sample is full
+        if self.format.is_none() {
+            self.select_format();
+        }
+        if let Some(record) = self.framer.flush() {
+            let originals: Vec<Option<String>> = self.originals.drain(..).collect();
+            self.process_lines(&record, &originals);
+        }
+
         // Convert to Vec and sort by occurrences (descending)
         let mut errors: Vec<ParsedError> = self.error_map.values().cloned().collect();
-        errors.sort_by(|a, b| b.occurrences.cmp(&a.occurrences));
+        errors.sort_by_key(|e| std::cmp::Reverse(e.occurrences));
 
         let unique_errors = errors.len();
 
         // Return top 20 only
         errors.truncate(20);
+        for error in &mut errors {
+            if let Some(timestamps) = self.occurrence_timestamps.get(&error.fingerprint) {
+                error.occurrence_timestamps = timestamps.iter().cloned().collect();
+            }
+        }
+        let histogram = self.histogram.build(errors.iter().map(|e| e.fingerprint.as_str()));
+        let bursts = bursts::detect(&self.burst_histogram, &self.options);
 
-        let result = ParseResult {
+        ParseResult {
             summary: LogStats {
                 total_lines: self.total_lines,
                 total_errors: self.total_errors,
                 total_warnings: self.total_warnings,
                 total_info: self.total_info,
                 unique_errors,
+                out_of_range_lines: self.out_of_range_lines,
+                invalid_bytes: self.line_decoder.invalid_bytes(),
             },
             errors,
+            format: self.format_detection.clone().unwrap_or(FormatDetection {
+                name: "text".to_string(),
+                confidence: 0.0,
+                forced: false,
+            }),
+            histogram,
+            bursts,
+            compression: self.decompressor.compression(),
+            decompression_error: self.decompression_error.clone(),
+        }
+    }
+
+    /// Detect the format from the sampled lines, then replay them
+    fn select_format(&mut self) {
+        let sample = std::mem::take(&mut self.sample);
+        let lines: Vec<Cow<str>> = sample.iter().map(|line| self.sanitize(line)).collect();
+        let lines: Vec<&str> = lines.iter().map(|line| line.as_ref()).collect();
+
+        let (format, confidence) = formats::detect_format(&lines, &self.options);
+        self.format = Some(format);
+        self.format_detection = Some(FormatDetection {
+            name: format.name().to_string(),
+            confidence,
+            forced: false,
+        });
+
+        for line in &sample {
+            self.frame_line(line);
+        }
+    }
+
+    /// Strip escape sequences and control characters, unless the options opt out
+    fn sanitize<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        if self.options.strip_control_sequences {
+            sanitize::strip_control_sequences(line)
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    /// Feed a line to the framer and process the record it completes, if any
+    fn frame_line(&mut self, line: &str) {
+        let format = self.format.unwrap_or(&formats::text::TextFormat);
+        let clean = self.sanitize(line);
+        self.originals.push_back(match clean {
+            Cow::Borrowed(_) => None,
+            Cow::Owned(_) => Some(line.to_string()),
+        });
+
+        if let Some(record) = self.framer.push(&clean, format, &self.options) {
+            // The completed record is everything before this line
+            let originals: Vec<Option<String>> = self.originals.drain(..record.len()).collect();
+            self.process_lines(&record, &originals);
+        }
+    }
+
+    /// Process one logical record (header line plus continuation lines)
+    /// `lines` are sanitized; `originals` holds the logged text of lines that were changed
+    fn process_lines(&mut self, lines: &[String], originals: &[Option<String>]) {
+        let Some(header) = lines.first() else {
+            return;
+        };
+        let format = self.format.unwrap_or(&formats::text::TextFormat);
+        let record = format.decode(header, &self.options);
+
+        if let Some(range) = self.time_range {
+            let timestamp = match &record {
+                Some(record) => record.timestamp.as_deref().and_then(|t| self.timestamps.parse(t)),
+                None => self.timestamps.find(header),
+            };
+            // A record without a timestamp continues the one before it
+            let timestamp = timestamp.or(self.last_timestamp);
+            self.last_timestamp = timestamp;
+            if timestamp.is_some_and(|t| !range.contains(&t)) {
+                self.out_of_range_lines += lines.len();
+                return;
+            }
+        }
+
+        // full_trace shows the record as logged, colour codes included
+        let mut trace: Vec<String> = lines.iter()
+            .zip(originals)
+            .map(|(line, original)| original.as_ref().unwrap_or(line).clone())
+            .collect();
+
+        // Obfuscated JVM builds: translate class and method names back before anything
+        // is decoded, templated or fingerprinted
+        let retraced;
+        let lines = if self.proguard.is_empty() {
+            lines
+        } else {
+            retraced = self.proguard.retrace(lines);
+            trace = self.proguard.retrace(&trace);
+            &retraced
+        };
+        let trace = trace.join("\n");
+
+        // Structured records carry their own level, message and stack
+        match record {
+            Some(record) => self.process_record(lines, record, trace),
+            None => self.process_text_record(lines, trace),
+        }
+    }
+
+    /// Handle a plain-text record
+    /// The header decides the error type; a record whose header is not an error can still
+    /// carry an exception line further down (e.g. an uncaught exception under an INFO line)
+    fn process_text_record(&mut self, lines: &[String], trace: String) {
+        let header = lines[0].as_str();
+
+        // "Completed 500 Internal Server Error" is request bookkeeping - the exception
+        // Rails logs right after it is the error
+        if self.rails_requests.observe(header) {
+            self.total_info += 1;
+            return;
+        }
+
+        // PHP names its level explicitly; "PHP Notice: Undefined index: error" is not an error
+        match traces::php::level(header) {
+            Some(formats::Level::Warn) => {
+                self.total_warnings += 1;
+                return;
+            }
+            Some(level) if level < formats::Level::Warn => {
+                self.total_info += 1;
+                return;
+            }
+            _ => {}
+        }
+
+        if let Some(exception) = self.decode_exception(lines) {
+            let (file, line_num, column) = exception.location();
+            let severity = if exception.fatal {
+                Severity::Critical
+            } else {
+                determine_severity(&ErrorType::Error, &exception.message)
+            };
+
+            self.record_event(ErrorEvent {
+                severity,
+                error_type: ErrorType::Error,
+                exception_type: exception.exception_type,
+                message: exception.message,
+                trace,
+                file,
+                line: line_num,
+                column,
+                timestamp: self.timestamps.find(header),
+                frames: exception.frames,
+                causes: exception.causes,
+                frame_signature: exception.frame_signature,
+                hostname: None,
+                app_name: None,
+                pid: None,
+                attributes: exception.attributes,
+            });
+            return;
+        }
+
+        let error_line = if is_error_line(header) {
+            Some(header)
+        } else {
+            lines[1..].iter().map(String::as_str).find(|l| is_exception_line(l))
+        };
+
+        let Some(error_line) = error_line else {
+            // Not an error - might be a warning or info
+            if GENERIC_WARN.is_match(header) {
+                self.total_warnings += 1;
+            } else if GENERIC_INFO.is_match(header) {
+                self.total_info += 1;
+            }
+            return;
+        };
+
+        let error_type = determine_error_type(error_line);
+
+        // Extract error details
+        let message = extract_error_message(error_line);
+
+        // Location from the error line itself, otherwise from the first stack frame
+        // This is important for multi-line stack traces where the error message
+        // doesn't contain the location, but the stack trace does
+        let (file, line_num, column) = std::iter::once(error_line)
+            .chain(lines[1..].iter().map(String::as_str))
+            .map(extract_location_any_format)
+            .find(|(file, _, _)| file.is_some())
+            .unwrap_or((None, None, None));
+
+        self.record_event(ErrorEvent {
+            severity: determine_severity(&error_type, &message),
+            error_type,
+            exception_type: None,
+            message,
+            trace,
+            file,
+            line: line_num,
+            column,
+            timestamp: self.timestamps.find(header),
+            frames: traces::parse_frames(lines[1..].iter().map(String::as_str)),
+            causes: Vec::new(),
+            frame_signature: None,
+            hostname: None,
+            app_name: None,
+            pid: None,
+            attributes: BTreeMap::new(),
+        });
+    }
+
+    /// Run the language decoders over a trace
+    /// Language decoders understand whole traces: Python tracebacks end with the
+    /// exception line, Java, .NET and Node print their cause chains around the frames,
+    /// Go dumps one block per goroutine, Rust prints the panic payload after the header,
+    /// Rails prints the exception class and message before an unindented backtrace,
+    /// PHP names the level and throw location in the header, sanitizers print several stacks
+    fn decode_exception(&mut self, lines: &[String]) -> Option<DecodedException> {
+        traces::php::decode(lines)
+            .or_else(|| traces::python::decode(lines))
+            .or_else(|| traces::dotnet::decode(lines))
+            .or_else(|| traces::java::decode(lines))
+            .or_else(|| traces::node::decode(lines))
+            .or_else(|| traces::go::decode(lines))
+            .or_else(|| traces::rust::decode(lines))
+            .or_else(|| traces::native::decode(lines))
+            .or_else(|| traces::ruby::decode(lines).map(|e| self.rails_requests.link(e)))
+    }
+
+    /// Handle a record decoded from a structured format (JSON Lines, logfmt, syslog)
+    /// The stack comes from a field; raw stack lines printed after the record are appended to it
+    fn process_record(&mut self, lines: &[String], record: DecodedRecord, trace: String) {
+        let raw_line = lines[0].as_str();
+
+        // JSON escapes such as "\u001b[31m" decode into real escape characters
+        let record_message = record.message.map(|message| self.sanitize(&message).into_owned());
+
+        let error_type = match record.level {
+            Some(level) => level.error_type(),
+            None => determine_error_type(record_message.as_deref().unwrap_or(raw_line)),
         };
 
-        serde_wasm_bindgen::to_value(&result).unwrap()
+        let record_stack = record.stack.map(|stack| {
+            if self.proguard.is_empty() {
+                return stack;
+            }
+            let stack_lines: Vec<String> = stack.lines().map(str::to_string).collect();
+            self.proguard.retrace(&stack_lines).join("\n")
+        });
+        let error_name = record.error_name.map(|name| {
+            self.proguard.class_name(&name).map_or(name, str::to_string)
+        });
+
+        let stack = match (record_stack, lines.len() > 1) {
+            (Some(stack), true) => Some(format!("{}\n{}", stack, lines[1..].join("\n"))),
+            (None, true) => Some(lines[1..].join("\n")),
+            (stack, false) => stack,
+        };
+
+        // Records are grouped when they are logged as errors or carry an error object
+        let is_error_record = error_type == ErrorType::Error ||
+                              error_name.is_some() ||
+                              stack.is_some();
+
+        if !is_error_record {
+            if error_type == ErrorType::Warning {
+                self.total_warnings += 1;
+            } else {
+                self.total_info += 1;
+            }
+            return;
+        }
+
+        // The stack field holds a whole trace, cause chain included
+        let stack_lines: Vec<String> = stack.as_deref()
+            .map(|stack| stack.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        let exception = self.decode_exception(&stack_lines);
+
+        let message = record_message
+            .or_else(|| exception.as_ref().map(|e| e.message.clone()).filter(|m| !m.is_empty()))
+            .or_else(|| stack_lines.first().map(|line| extract_error_message(line)))
+            .or_else(|| error_name.clone())
+            .unwrap_or_else(|| raw_line.trim().to_string());
+
+        let severity = if record.level == Some(formats::Level::Fatal) || exception.as_ref().is_some_and(|e| e.fatal) {
+            Severity::Critical
+        } else {
+            determine_severity(&error_type, &message)
+        };
+
+        let (file, line_num, column) = match &exception {
+            Some(exception) => exception.location(),
+            // Location comes from the first stack line any language extractor understands
+            None => stack_lines.iter()
+                .map(|line| extract_location_any_format(line))
+                .find(|(file, _, _)| file.is_some())
+                .unwrap_or((None, None, None)),
+        };
+        let exception_type = error_name.or_else(|| exception.as_ref().and_then(|e| e.exception_type.clone()));
+
+        let mut attributes = record.attributes;
+        let (frames, causes, frame_signature) = match exception {
+            Some(exception) => {
+                for (key, value) in exception.attributes {
+                    attributes.entry(key).or_insert(value);
+                }
+                (exception.frames, exception.causes, exception.frame_signature)
+            }
+            None => (traces::parse_frames(stack_lines.iter().map(String::as_str)), Vec::new(), None),
+        };
+
+        self.record_event(ErrorEvent {
+            error_type,
+            severity,
+            exception_type,
+            message,
+            trace: stack.unwrap_or(trace),
+            file,
+            line: line_num,
+            column,
+            timestamp: record.timestamp.as_deref().and_then(|t| self.timestamps.parse(t)),
+            frames,
+            causes,
+            frame_signature,
+            hostname: record.hostname,
+            app_name: record.app_name,
+            pid: record.pid,
+            attributes,
+        });
+    }
+
+    /// Count an error occurrence and merge it into its fingerprint group
+    fn record_event(&mut self, mut event: ErrorEvent) {
+        // Wrapper exceptions hide what actually failed - optionally group by the root cause
+        if self.options.group_by_root_cause {
+            if let Some(root) = traces::root_cause(&event.causes) {
+                event.exception_type = root.exception_type.clone();
+                event.message = root.message.clone();
+                event.file = root.file.clone();
+                event.line = root.line;
+                event.column = root.column;
+                event.frames = root.frames.clone();
+            }
+        }
+
+        // Minified bundles: rewrite frames and the location to the original sources, so
+        // grouping survives redeploys that rename the bundle
+        if !self.source_maps.is_empty() {
+            if let (Some(file), Some(line), Some(column)) = (&event.file, event.line, event.column) {
+                if let Some(original) = self.source_maps.lookup(file, line, column) {
+                    (event.file, event.line, event.column) = (Some(original.file), Some(original.line), Some(original.column));
+                }
+            }
+            self.source_maps.resolve(&mut event.frames);
+            for cause in &mut event.causes {
+                self.source_maps.resolve(&mut cause.frames);
+            }
+        }
+
+        // Report the application frame nearest the error rather than the library that threw
+        let in_app_prefixes = &self.options.in_app_prefixes;
+        if let Some(top) = traces::in_app::classify(&mut event.frames, in_app_prefixes) {
+            let frame = &event.frames[top];
+            (event.file, event.line, event.column) = (frame.file.clone(), frame.line, frame.column);
+        }
+        for cause in &mut event.causes {
+            if let Some(top) = traces::in_app::classify(&mut cause.frames, in_app_prefixes) {
+                let frame = &cause.frames[top];
+                (cause.file, cause.line, cause.column) = (frame.file.clone(), frame.line, frame.column);
+            }
+        }
+
+        // Extract template and variables from message
+        let (template, variables) = extract_template(&event.message);
+
+        // Use template for fingerprinting to group similar errors
+        // Native crash reports group on their normalised frames, which survive rebuilds
+        let fingerprint = match &event.frame_signature {
+            Some(frames) => generate_fingerprint(&format!("{}\n{}", template, frames), &None, &None),
+            None => generate_fingerprint(&template, &event.file, &event.line),
+        };
+
+        // Update counts
+        match event.error_type {
+            ErrorType::Error => self.total_errors += 1,
+            ErrorType::Warning => self.total_warnings += 1,
+            ErrorType::Info => self.total_info += 1,
+        }
+
+        self.histogram.record(&fingerprint, event.timestamp.as_ref());
+        self.burst_histogram.record(&fingerprint, event.timestamp.as_ref());
+        let timestamp = event.timestamp.as_ref().map(timestamps::format_timestamp);
+        // Keep the most recent occurrences, dropping the oldest once the limit is reached
+        let max_timestamps = self.options.max_occurrence_timestamps;
+        if let Some(timestamp) = timestamp.as_ref().filter(|_| max_timestamps > 0) {
+            let timestamps = self.occurrence_timestamps.entry(fingerprint.clone()).or_default();
+            if timestamps.len() >= max_timestamps {
+                timestamps.pop_front();
+            }
+            timestamps.push_back(timestamp.clone());
+        }
+
+        // Check if we've seen this error before
+        if let Some(existing) = self.error_map.get_mut(&fingerprint) {
+            // Increment occurrence count
+            existing.occurrences += 1;
+            // RFC 3339 in UTC with fixed precision sorts as text
+            if let Some(timestamp) = timestamp {
+                if existing.first_seen.as_ref().is_none_or(|first| timestamp < *first) {
+                    existing.first_seen = Some(timestamp.clone());
+                }
+                if existing.last_seen.as_ref().is_none_or(|last| timestamp > *last) {
+                    existing.last_seen = Some(timestamp.clone());
+                }
+            }
+            // Add variables from this occurrence
+            existing.variables.extend(variables);
+            // Update full trace with new occurrence
+            existing.full_trace.push_str("\n\n---\n\n");
+            existing.full_trace.push_str(&event.trace);
+        } else {
+            // New error - create entry
+            let parsed_error = ParsedError {
+                id: uuid::Uuid::new_v4().to_string(),
+                error_type: event.error_type,
+                severity: event.severity,
+                exception_type: event.exception_type,
+                message: event.message,
+                template,
+                variables,
+                full_trace: event.trace,
+                file: event.file,
+                line: event.line,
+                column: event.column,
+                occurrences: 1,
+                first_seen: timestamp.clone(),
+                last_seen: timestamp.clone(),
+                // Filled in from `occurrence_timestamps` when the result is built
+                occurrence_timestamps: Vec::new(),
+                timestamp,
+                fingerprint: fingerprint.clone(),
+                frames: event.frames,
+                causes: event.causes,
+                hostname: event.hostname,
+                app_name: event.app_name,
+                pid: event.pid,
+                attributes: event.attributes,
+            };
+
+            self.error_map.insert(fingerprint.clone(), parsed_error);
+        }
     }
 }
 
@@ -880,10 +1418,12 @@ impl LogParser {
 // WASM EXPORTS (Legacy - kept for backwards compatibility)
 // ============================================================================
 
+/// Parse a whole log in one call
+/// `options` is an optional ParserOptions object (see options.rs)
 #[wasm_bindgen]
-pub fn parse_log(content: &str) -> JsValue {
-    let result = parse_log_content(content);
-    serde_wasm_bindgen::to_value(&result).unwrap()
+pub fn parse_log(content: &str, options: Option<JsValue>) -> JsValue {
+    let result = parse_log_content(content, ParserOptions::from_js(options));
+    to_js(&result)
 }
 
 // For debugging - export individual functions
@@ -961,3 +1501,597 @@ pub fn cluster_errors(errors: JsValue, threshold: f32) -> JsValue {
     let clusters = pattern_learning::cluster_by_similarity(&errors, threshold);
     serde_wasm_bindgen::to_value(&clusters).unwrap_or(JsValue::NULL)
 }
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> ParseResult {
+        parse_log_content(content, ParserOptions::default())
+    }
+
+    #[test]
+    fn test_text_error_with_stack_trace() {
+        let result = parse(
+            "2025-05-27 00:40:12 ERROR TypeError: Cannot read property 'id' of undefined\n    at getUser (/app/src/users.js:12:7)\n2025-05-27 00:40:13 INFO done",
+        );
+
+        assert_eq!(result.summary.total_lines, 3);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.file.as_deref(), Some("/app/src/users.js"));
+        assert_eq!(error.line, Some(12));
+        assert!(error.full_trace.contains("getUser"));
+    }
+
+    #[test]
+    fn test_json_lines_records() {
+        let content = [
+            r#"{"level":30,"time":"2026-10-16T23:05:00Z","msg":"server started"}"#,
+            r#"{"level":50,"time":"2026-10-16T23:05:01Z","msg":"User 12345 not found","err":{"type":"NotFoundError","stack":"NotFoundError: User 12345 not found\n    at find (/app/src/users.js:40:11)"}}"#,
+            r#"{"level":50,"time":"2026-10-16T23:05:02Z","msg":"User 67890 not found","err":{"type":"NotFoundError","stack":"NotFoundError: User 67890 not found\n    at find (/app/src/users.js:40:11)"}}"#,
+        ].join("\n");
+        let result = parse(&content);
+
+        assert_eq!(result.summary.total_info, 1);
+        assert_eq!(result.summary.total_errors, 2);
+        assert_eq!(result.errors.len(), 1);
+
+        let error = &result.errors[0];
+        assert_eq!(error.message, "User 12345 not found");
+        assert_eq!(error.template, "User {ID} not found");
+        assert_eq!(error.occurrences, 2);
+        assert_eq!(error.timestamp.as_deref(), Some("2026-10-16T23:05:01.000Z"));
+        assert_eq!(error.file.as_deref(), Some("/app/src/users.js"));
+        assert_eq!(error.line, Some(40));
+        assert!(error.full_trace.starts_with("NotFoundError: User 12345 not found"));
+    }
+
+    #[test]
+    fn test_logfmt_records_keep_attributes() {
+        let result = parse(
+            "level=info msg=\"request served\" status=200\nlevel=error msg=\"db timeout\" user_id=123 duration=3.2s",
+        );
+
+        assert_eq!(result.summary.total_info, 1);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.message, "db timeout");
+        assert_eq!(error.attributes.get("user_id").map(String::as_str), Some("123"));
+        assert_eq!(error.attributes.get("duration").map(String::as_str), Some("3.2s"));
+    }
+
+    #[test]
+    fn test_syslog_records() {
+        let result = parse(
+            "<11>1 2026-10-16T23:05:01Z web01 billing 1234 ID47 - Payment 99812 rejected\n<14>1 2026-10-16T23:05:02Z web01 billing 1234 - - heartbeat\n<10>Oct 16 23:05:03 web02 kernel: CPU 3 machine check",
+        );
+
+        assert_eq!(result.summary.total_errors, 2);
+        assert_eq!(result.summary.total_info, 1);
+
+        let rejected = result.errors.iter().find(|e| e.template == "Payment {ID} rejected").unwrap();
+        assert_eq!(rejected.severity, Severity::Medium);
+        assert_eq!(rejected.hostname.as_deref(), Some("web01"));
+        assert_eq!(rejected.app_name.as_deref(), Some("billing"));
+        assert_eq!(rejected.pid.as_deref(), Some("1234"));
+
+        let kernel = result.errors.iter().find(|e| e.app_name.as_deref() == Some("kernel")).unwrap();
+        assert_eq!(kernel.severity, Severity::Critical);
+    }
+
+    #[test]
+    fn test_format_detection_is_reported() {
+        let result = parse(r#"{"level":30,"msg":"a"}
+{"level":50,"msg":"request failed","err":{"type":"TypeError"}}
+    at handler (/app/src/routes.js:8:3)"#);
+
+        assert_eq!(result.format.name, "json");
+        assert!(!result.format.forced);
+        assert_eq!(result.errors.len(), 1);
+        // The raw stack line after the JSON record is attached to it
+        assert_eq!(result.errors[0].file.as_deref(), Some("/app/src/routes.js"));
+        assert!(result.errors[0].full_trace.contains("handler"));
+    }
+
+    #[test]
+    fn test_forced_format() {
+        let options = ParserOptions {
+            format: Some("text".to_string()),
+            ..ParserOptions::default()
+        };
+        let result = parse_log_content(r#"level=error msg="db timeout""#, options);
+
+        assert_eq!(result.format.name, "text");
+        assert!(result.format.forced);
+        assert_eq!(result.errors[0].message, r#"level=error msg="db timeout""#);
+        assert!(result.errors[0].attributes.is_empty());
+    }
+
+    #[test]
+    fn test_timestamp_prefix_framing() {
+        let options = ParserOptions {
+            framing: framing::FramingRule::TimestampPrefix,
+            ..ParserOptions::default()
+        };
+        let mut parser = LogParser::with_options(options);
+        for line in [
+            "2026-10-16 23:05:01 INFO Handling request 42",
+            "TypeError: Cannot read properties of undefined (reading 'id')",
+            "    at handler (/app/src/routes.js:8:3)",
+            "2026-10-16 23:05:02 DEBUG query plan:",
+            "    Seq Scan on errors  (cost=0.00..35.50 rows=2550)",
+        ] {
+            parser.process_line(line);
+        }
+        let result = parser.build_result();
+
+        assert_eq!(result.summary.total_errors, 1);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.timestamp.as_deref(), Some("2026-10-16T23:05:01.000Z"));
+        assert_eq!(error.file.as_deref(), Some("/app/src/routes.js"));
+        assert!(!error.full_trace.contains("Seq Scan"));
+    }
+
+    #[test]
+    fn test_first_and_last_seen() {
+        let options = ParserOptions {
+            max_occurrence_timestamps: 2,
+            ..ParserOptions::default()
+        };
+        let result = parse_log_content(r#"{"time":"2026-10-16T23:05:02Z","level":"error","msg":"Connection refused"}
+{"time":"2026-10-16T23:05:01Z","level":"error","msg":"Connection refused"}
+{"time":"2026-10-16T23:09:30Z","level":"error","msg":"Connection refused"}"#, options);
+
+        let error = &result.errors[0];
+        assert_eq!(error.occurrences, 3);
+        assert_eq!(error.timestamp.as_deref(), Some("2026-10-16T23:05:02.000Z"));
+        assert_eq!(error.first_seen.as_deref(), Some("2026-10-16T23:05:01.000Z"));
+        assert_eq!(error.last_seen.as_deref(), Some("2026-10-16T23:09:30.000Z"));
+        assert_eq!(error.occurrence_timestamps, vec!["2026-10-16T23:05:01.000Z", "2026-10-16T23:09:30.000Z"]);
+    }
+
+    #[test]
+    fn test_histogram_follows_reported_errors() {
+        let options = ParserOptions {
+            histogram_bucket_seconds: Some(60),
+            ..ParserOptions::default()
+        };
+        let result = parse_log_content(r#"{"time":"2026-10-16T23:05:02Z","level":"error","msg":"Connection refused"}
+{"time":"2026-10-16T23:05:40Z","level":"error","msg":"Connection refused"}
+{"time":"2026-10-16T23:07:10Z","level":"error","msg":"Connection refused"}
+{"level":"error","msg":"Connection refused"}"#, options);
+
+        let histogram = &result.histogram;
+        assert_eq!(histogram.start.as_deref(), Some("2026-10-16T23:05:00.000Z"));
+        assert_eq!(histogram.total, vec![2, 0, 1]);
+        assert_eq!(histogram.by_fingerprint[&result.errors[0].fingerprint], vec![2, 0, 1]);
+        assert_eq!(histogram.untimed, 1);
+    }
+
+    #[test]
+    fn test_bursts_name_the_spiking_error() {
+        let mut content = String::new();
+        for minute in 0..30 {
+            content.push_str(&format!("{{\"time\":\"2026-10-16T23:{:02}:00Z\",\"level\":\"error\",\"msg\":\"Cache miss\"}}\n", minute));
+        }
+        for _ in 0..20 {
+            content.push_str("{\"time\":\"2026-10-16T23:04:10Z\",\"level\":\"error\",\"msg\":\"Disk full\"}\n");
+        }
+
+        let result = parse(&content);
+        let disk_full = result.errors.iter().find(|e| e.message == "Disk full").unwrap();
+        assert_eq!(result.bursts.len(), 1);
+        assert!(result.bursts[0].start.starts_with("2026-10-16T23:04:"));
+        assert_eq!(result.bursts[0].fingerprints, vec![disk_full.fingerprint.clone()]);
+    }
+
+    #[test]
+    fn test_time_range_filter() {
+        let options = ParserOptions {
+            time_range: Some(timestamps::TimeRange {
+                from: Some("2026-10-16T23:00:00Z".to_string()),
+                to: Some("2026-10-16T23:20:00Z".to_string()),
+            }),
+            ..ParserOptions::default()
+        };
+        let result = parse_log_content("2026-10-16 22:59:59 ERROR TypeError: Cannot read properties of undefined
+    at render (/app/src/view.js:10:5)
+2026-10-16 23:05:01 ERROR RangeError: Maximum call stack size exceeded
+    at loop (/app/src/loop.js:3:1)
+retrying
+2026-10-16 23:20:00 ERROR SyntaxError: Unexpected token
+too late", options);
+
+        assert_eq!(result.summary.total_lines, 7);
+        assert_eq!(result.summary.out_of_range_lines, 4);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].message, "Maximum call stack size exceeded");
+    }
+
+    #[test]
+    fn test_compressed_chunks() {
+        use std::io::Write;
+
+        let log = "2026-10-16 23:05:01 ERROR TypeError: Cannot read properties of undefined
+    at render (/app/src/view.js:10:5)
+2026-10-16 23:05:02 INFO done";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(log.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut parser = LogParser::with_options(ParserOptions::default());
+        for chunk in compressed.chunks(3) {
+            parser.process_chunk(chunk);
+        }
+        parser.finish();
+        let result = parser.build_result();
+
+        assert_eq!(result.compression, Some(Compression::Gzip));
+        assert_eq!(result.decompression_error, None);
+        assert_eq!(result.summary.total_lines, 3);
+        assert_eq!(result.summary.total_errors, 1);
+        assert_eq!(result.errors[0].file.as_deref(), Some("/app/src/view.js"));
+
+        // A download cut short: what was read is parsed, and the result says it's incomplete
+        let mut parser = LogParser::with_options(ParserOptions::default());
+        parser.process_chunk(&compressed[..compressed.len() - 10]);
+        parser.finish();
+        assert!(parser.build_result().decompression_error.is_some());
+    }
+
+    #[test]
+    fn test_utf16_chunks_with_crlf() {
+        let log = "2026-10-16 23:05:01 ERROR TypeError: Cannot read properties of undefined\r
+    at render (/app/src/vi\u{e9}w.js:10:5)\r
+2026-10-16 23:05:02 INFO done\r\n";
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(log.encode_utf16().flat_map(u16::to_le_bytes));
+        // An unpaired surrogate
+        bytes.extend([0x00, 0xdc]);
+
+        let mut parser = LogParser::with_options(ParserOptions::default());
+        for chunk in bytes.chunks(5) {
+            parser.process_chunk(chunk);
+        }
+        parser.finish();
+        let result = parser.build_result();
+
+        assert_eq!(result.summary.total_lines, 4);
+        assert_eq!(result.summary.total_errors, 1);
+        assert_eq!(result.summary.invalid_bytes, 2);
+        assert_eq!(result.errors[0].file.as_deref(), Some("/app/src/vi\u{e9}w.js"));
+    }
+
+    #[test]
+    fn test_python_traceback_attaches_to_exception() {
+        let result = parse(r#"2026-10-16 23:05:01,120 INFO Starting worker
+2026-10-16 23:05:02,481 ERROR Job 4411 failed
+Traceback (most recent call last):
+  File "/usr/lib/python3.12/site-packages/celery/app/trace.py", line 451, in trace_task
+    R = retval = fun(*args, **kwargs)
+  File "/app/jobs/billing.py", line 88, in charge
+    raise ValueError(f"invalid amount {amount}")
+ValueError: invalid amount -5
+2026-10-16 23:05:03,002 INFO Job 4412 done"#);
+
+        assert_eq!(result.summary.total_errors, 1);
+        assert_eq!(result.summary.total_info, 2);
+        let error = &result.errors[0];
+        assert_eq!(error.exception_type.as_deref(), Some("ValueError"));
+        assert_eq!(error.message, "invalid amount -5");
+        assert_eq!(error.file.as_deref(), Some("/app/jobs/billing.py"));
+        assert_eq!(error.line, Some(88));
+        assert_eq!(error.timestamp.as_deref(), Some("2026-10-16T23:05:02.481Z"));
+        assert!(error.full_trace.starts_with("2026-10-16 23:05:02,481 ERROR Job 4411 failed"));
+        assert!(error.full_trace.ends_with("ValueError: invalid amount -5"));
+    }
+
+    #[test]
+    fn test_json_fatal_level_is_critical() {
+        let result = parse(r#"{"level":"fatal","message":"worker crashed"}"#);
+        assert_eq!(result.errors[0].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn test_node_cause_block_closing_brace() {
+        let result = parse("Error: Failed to load user 42
+    at loadUser (/app/src/users.js:30:11)
+    at async handler (/app/src/routes.js:8:3) {
+  [cause]: Error: connect ECONNREFUSED 10.0.0.5:5432
+      at TCPConnectWrap.afterConnect [as oncomplete] (node:net:1555:16)
+}
+2026-10-16 23:05:02 ERROR Invalid config override {
+}");
+        let error = result.errors.iter().find(|e| e.message.contains("Failed to load")).unwrap();
+        assert!(error.full_trace.ends_with('}'));
+        assert_eq!(error.causes.len(), 1);
+
+        // The brace after a plain log line is a record of its own
+        let config = result.errors.iter().find(|e| e.message.contains("Invalid config")).unwrap();
+        assert!(!config.full_trace.contains('}'));
+    }
+
+    #[test]
+    fn test_group_by_root_cause() {
+        let content = r#"org.springframework.web.util.NestedServletException: Request processing failed
+	at org.springframework.web.servlet.FrameworkServlet.processRequest(FrameworkServlet.java:1014)
+Caused by: java.net.SocketTimeoutException: Read timed out
+	at com.acme.billing.Gateway.charge(Gateway.java:88)
+	... 12 more
+org.springframework.web.util.NestedServletException: Request processing failed
+	at org.springframework.web.servlet.FrameworkServlet.processRequest(FrameworkServlet.java:1014)
+Caused by: java.sql.SQLException: Connection refused
+	at com.acme.users.Repository.find(Repository.java:31)
+	... 12 more"#;
+
+        let result = parse(content);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].occurrences, 2);
+        assert_eq!(result.errors[0].causes.len(), 1);
+
+        let result = parse_log_content(content, ParserOptions {
+            group_by_root_cause: true,
+            ..ParserOptions::default()
+        });
+        assert_eq!(result.errors.len(), 2);
+        let timeout = result.errors.iter()
+            .find(|e| e.exception_type.as_deref() == Some("java.net.SocketTimeoutException"))
+            .unwrap();
+        assert_eq!(timeout.message, "Read timed out");
+        assert_eq!(timeout.file.as_deref(), Some("Gateway.java"));
+        assert_eq!(timeout.line, Some(88));
+        assert_eq!(timeout.causes[0].exception_type.as_deref(), Some("java.net.SocketTimeoutException"));
+    }
+
+    #[test]
+    fn test_go_panic_dump_is_one_error() {
+        let result = parse("2026-10-16 23:05:01 INFO listening on :8080
+panic: runtime error: index out of range [5] with length 3
+
+goroutine 12 [running]:
+main.(*Cart).Item(...)
+\t/app/cart.go:18
+main.checkout(0xc000010000)
+\t/app/checkout.go:44 +0x1c
+
+goroutine 1 [IO wait]:
+internal/poll.runtime_pollWait(0x7f3c, 0x72)
+\t/usr/local/go/src/runtime/netpoll.go:345 +0x85
+exit status 2
+2026-10-16 23:05:03 INFO restarted");
+
+        assert_eq!(result.summary.total_errors, 1);
+        assert_eq!(result.summary.total_info, 2);
+        let error = &result.errors[0];
+        assert_eq!(error.severity, Severity::Critical);
+        assert_eq!(error.exception_type.as_deref(), Some("panic"));
+        assert_eq!(error.message, "runtime error: index out of range [5] with length 3");
+        assert_eq!(error.file.as_deref(), Some("/app/cart.go"));
+        assert_eq!(error.line, Some(18));
+        assert_eq!(error.attributes.get("goroutine_states").map(String::as_str), Some("1 IO wait"));
+    }
+
+    #[test]
+    fn test_rust_panic_is_critical() {
+        let result = parse("2026-10-16 23:05:01 INFO loading config
+thread 'main' panicked at src/config.rs:12:40:
+failed to read config: No such file or directory (os error 2)
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace");
+
+        assert_eq!(result.summary.total_errors, 1);
+        let error = &result.errors[0];
+        assert_eq!(error.severity, Severity::Critical);
+        assert_eq!(error.message, "failed to read config: No such file or directory (os error 2)");
+        assert_eq!(error.file.as_deref(), Some("src/config.rs"));
+        assert_eq!(error.line, Some(12));
+        assert_eq!(error.column, Some(40));
+    }
+
+    #[test]
+    fn test_dotnet_exception_in_json_record() {
+        let result = parse(r#"{"@t":"2026-10-16T23:05:01Z","level":"Error","message":"Request failed","exception":"System.NullReferenceException: Object reference not set to an instance of an object.\n   at Orders.Controller.Get(Int32 id) in /src/Orders/Controller.cs:line 15"}"#);
+
+        let error = &result.errors[0];
+        assert_eq!(error.file.as_deref(), Some("/src/Orders/Controller.cs"));
+        assert_eq!(error.line, Some(15));
+    }
+
+    #[test]
+    fn test_java_cause_chain_in_json_stack_field() {
+        let content = [
+            r#"{"level":"ERROR","message":"Request processing failed","stack_trace":"org.springframework.web.util.NestedServletException: Request processing failed\n\tat org.springframework.web.servlet.FrameworkServlet.processRequest(FrameworkServlet.java:1014)\nCaused by: java.net.SocketTimeoutException: Read timed out\n\tat com.acme.billing.Gateway.charge(Gateway.java:88)\n\t... 12 more"}"#,
+            r#"{"level":"ERROR","message":"Request processing failed","stack_trace":"org.springframework.web.util.NestedServletException: Request processing failed\n\tat org.springframework.web.servlet.FrameworkServlet.processRequest(FrameworkServlet.java:1014)\nCaused by: java.sql.SQLException: Connection refused\n\tat com.acme.users.Repository.find(Repository.java:31)\n\t... 12 more"}"#,
+        ].join("\n");
+
+        let result = parse(&content);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].exception_type.as_deref(), Some("org.springframework.web.util.NestedServletException"));
+        assert_eq!(result.errors[0].causes.len(), 1);
+
+        let result = parse_log_content(&content, ParserOptions {
+            group_by_root_cause: true,
+            ..ParserOptions::default()
+        });
+        assert_eq!(result.errors.len(), 2);
+        let timeout = result.errors.iter()
+            .find(|e| e.exception_type.as_deref() == Some("java.net.SocketTimeoutException"))
+            .unwrap();
+        assert_eq!(timeout.file.as_deref(), Some("Gateway.java"));
+        assert_eq!(timeout.line, Some(88));
+    }
+
+    #[test]
+    fn test_location_is_top_in_app_frame() {
+        let log = "2026-10-16 23:05:01 ERROR TypeError: Cannot read properties of undefined (reading 'id')
+    at Layer.handle [as handle_request] (/srv/app/node_modules/express/lib/router/layer.js:95:5)
+    at loadUser (/srv/app/src/users.js:12:11)";
+
+        let error = &parse(log).errors[0];
+        assert_eq!(error.file.as_deref(), Some("/srv/app/src/users.js"));
+        assert_eq!(error.line, Some(12));
+        assert!(!error.frames[0].in_app && error.frames[1].in_app);
+
+        // Claiming the router as application code moves the location back to it
+        let options = ParserOptions {
+            in_app_prefixes: vec!["/srv/app/node_modules/express/".to_string()],
+            ..ParserOptions::default()
+        };
+        let error = &parse_log_content(log, options).errors[0];
+        assert_eq!(error.line, Some(95));
+    }
+
+    #[test]
+    fn test_source_maps_keep_groups_across_deploys() {
+        // Two deploys of the same code, hashed differently
+        let map = |bundle: &str| format!(r#"{{"version":3,"file":"{}","sources":["src/users.ts"],"names":["loadUser"],"mappings":"SAASA,IACP,8BAGO,IACPA"}}"#, bundle);
+        let mut parser = LogParser::with_options(ParserOptions::default());
+        assert!(parser.add_source_map("main.3f9a.js.map", map("main.3f9a.js").as_bytes()));
+        assert!(parser.add_source_map("main.77c1.js.map", map("main.77c1.js").as_bytes()));
+        assert!(!parser.add_source_map("broken.js.map", b"{"));
+
+        for bundle in ["main.3f9a.js", "main.77c1.js"] {
+            parser.process_line("2026-10-16 23:05:01 ERROR Error: user missing");
+            parser.process_line(&format!("    at a (https://cdn.acme.com/js/{}:1:14)", bundle));
+            parser.process_line(&format!("    at b (https://cdn.acme.com/js/{}:1:48)", bundle));
+        }
+        let result = parser.build_result();
+
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.occurrences, 2);
+        assert_eq!(error.file.as_deref(), Some("src/users.ts"));
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        assert_eq!(error.frames[0].function.as_deref(), Some("loadUser"));
+    }
+
+    #[test]
+    fn test_proguard_mapping_deobfuscates_before_grouping() {
+        let mut parser = LogParser::with_options(ParserOptions::default());
+        assert!(parser.add_proguard_mapping(b"com.acme.orders.OrderService -> a.b.c:
+    5:8:com.acme.orders.Order load(long):40:43 -> a
+com.acme.orders.PaymentException -> a.b.d:
+"));
+        assert!(!parser.add_proguard_mapping(b"not a mapping"));
+
+        for line in ["a.b.d: Payment declined", "\tat a.b.c.a(SourceFile:6)"] {
+            parser.process_line(line);
+        }
+        let result = parser.build_result();
+
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.exception_type.as_deref(), Some("com.acme.orders.PaymentException"));
+        assert_eq!(error.file.as_deref(), Some("OrderService.java"));
+        assert_eq!(error.line, Some(41));
+        assert_eq!(error.frames[0].function.as_deref(), Some("load"));
+    }
+
+    #[test]
+    fn test_ansi_colours_are_stripped() {
+        // Same error, coloured differently by two loggers
+        let log = "\x1b[31mERROR:\x1b[0m Connection refused\n\
+                   \x1b[1;91mERROR:\x1b[0m Connection refused\x1b[K";
+
+        let result = parse(log);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.occurrences, 2);
+        assert!(!error.message.contains('\x1b'));
+        assert!(error.full_trace.starts_with("\x1b[31mERROR:"));
+
+        let options = ParserOptions {
+            strip_control_sequences: false,
+            ..ParserOptions::default()
+        };
+        // The anchored level patterns don't see the coloured ERROR at all
+        assert!(parse_log_content(log, options).errors.is_empty());
+    }
+
+    #[test]
+    fn test_frames_are_exposed() {
+        let result = parse("2026-10-16 23:05:01 ERROR Request failed
+    at com.acme.api.UserController.show(UserController.java:42)
+    at org.springframework.web.servlet.FrameworkServlet.service(FrameworkServlet.java:883)");
+
+        let frames = &result.errors[0].frames;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].module.as_deref(), Some("com.acme.api.UserController"));
+        assert_eq!(frames[0].function.as_deref(), Some("show"));
+        assert_eq!(frames[0].language, Some(traces::Language::Java));
+        assert_eq!(frames[1].raw, "at org.springframework.web.servlet.FrameworkServlet.service(FrameworkServlet.java:883)");
+    }
+
+    #[test]
+    fn test_rails_error_is_linked_to_request() {
+        let result = parse(r#"Started GET "/users/5" for 10.0.0.1 at 2026-10-16 23:05:01 +0000
+Processing by UsersController#show as HTML
+Completed 500 Internal Server Error in 9ms (ActiveRecord: 1.1ms)
+
+ActiveRecord::RecordNotFound (Couldn't find User with 'id'=5):
+
+app/models/user.rb:42:in `find_user'
+app/controllers/users_controller.rb:10:in `show'
+
+Started GET "/health" for 10.0.0.1 at 2026-10-16 23:05:02 +0000"#);
+
+        assert_eq!(result.summary.total_errors, 1);
+        let error = &result.errors[0];
+        assert_eq!(error.exception_type.as_deref(), Some("ActiveRecord::RecordNotFound"));
+        assert_eq!(error.message, "Couldn't find User with 'id'=5");
+        assert_eq!(error.file.as_deref(), Some("app/models/user.rb"));
+        assert_eq!(error.line, Some(42));
+        assert_eq!(error.attributes.get("request.path").map(String::as_str), Some("/users/5"));
+        assert_eq!(error.attributes.get("request.status").map(String::as_str), Some("500"));
+        assert_eq!(error.attributes.get("request.controller").map(String::as_str), Some("UsersController#show"));
+    }
+
+    #[test]
+    fn test_php_levels_and_fatal_errors() {
+        let result = parse("[16-Oct-2026 23:05:01 UTC] PHP Notice:  Undefined index: error in /var/www/app/a.php on line 3
+[16-Oct-2026 23:05:01 UTC] PHP Warning:  Undefined variable $user in /var/www/app/a.php on line 7
+[16-Oct-2026 23:05:02 UTC] PHP Fatal error:  Uncaught Exception: boom in /var/www/app/b.php:12
+Stack trace:
+#0 /var/www/app/index.php(5): run()
+#1 {main}
+  thrown in /var/www/app/b.php on line 12");
+
+        assert_eq!(result.summary.total_info, 1);
+        assert_eq!(result.summary.total_warnings, 1);
+        assert_eq!(result.summary.total_errors, 1);
+        let error = &result.errors[0];
+        assert_eq!(error.severity, Severity::Critical);
+        assert_eq!(error.exception_type.as_deref(), Some("Exception"));
+        assert_eq!(error.message, "boom");
+        assert_eq!(error.file.as_deref(), Some("/var/www/app/b.php"));
+        assert_eq!(error.line, Some(12));
+    }
+
+    #[test]
+    fn test_sanitizer_reports_group_across_runs() {
+        let report = |pid: u32, pc: &str| format!("=================================================================
+=={pid}==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x{pc}10 at pc 0x{pc}c3 bp 0x7ffd4 sp 0x7ffd8
+WRITE of size 1 at 0x{pc}10 thread T0
+    #0 0x{pc}c3 in decode_packet /src/net/packet.c:88:14
+    #1 0x{pc}f0 in main /src/main.c:30:5
+
+SUMMARY: AddressSanitizer: heap-buffer-overflow /src/net/packet.c:88:14 in decode_packet
+=={pid}==ABORTING
+");
+        let result = parse(&format!("{}{}", report(4242, "55d4c2a1"), report(977, "5600aa00")));
+
+        assert_eq!(result.summary.total_errors, 2);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.occurrences, 2);
+        assert_eq!(error.severity, Severity::Critical);
+        assert_eq!(error.message, "heap-buffer-overflow");
+        assert_eq!(error.file.as_deref(), Some("/src/net/packet.c"));
+        assert_eq!(error.line, Some(88));
+    }
+}
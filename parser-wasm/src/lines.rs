@@ -0,0 +1,272 @@
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+const UTF16_LE_BOM: [u8; 2] = [0xff, 0xfe];
+const UTF16_BE_BOM: [u8; 2] = [0xfe, 0xff];
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Text encoding of a byte stream, told by its byte order mark (UTF-8 without one)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    /// Holding the first bytes until a BOM can be ruled in or out
+    Detecting,
+    Utf8,
+    Utf16 { big_endian: bool },
+}
+
+/// Splits a byte stream into lines and decodes them, holding partial lines,
+/// characters and UTF-16 code units between chunks
+/// Lines end at "\n", "\r\n" or a lone "\r"; invalid sequences become U+FFFD
+#[derive(Debug)]
+pub struct LineDecoder {
+    encoding: Encoding,
+    /// Bytes held while detecting the encoding
+    head: Vec<u8>,
+    /// UTF-8 bytes of the line being read
+    partial: Vec<u8>,
+    /// The last line ended with "\r", so a "\n" starting the next chunk belongs to it
+    after_cr: bool,
+    /// UTF-16: the first byte of a code unit, and a high surrogate waiting for its pair
+    odd_byte: Option<u8>,
+    high_surrogate: Option<u16>,
+    invalid_bytes: usize,
+}
+
+impl Default for LineDecoder {
+    fn default() -> Self {
+        LineDecoder {
+            encoding: Encoding::Detecting,
+            head: Vec::new(),
+            partial: Vec::new(),
+            after_cr: false,
+            odd_byte: None,
+            high_surrogate: None,
+            invalid_bytes: 0,
+        }
+    }
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+impl LineDecoder {
+    /// Bytes replaced by U+FFFD so far (invalid UTF-8, unpaired UTF-16 surrogates)
+    pub fn invalid_bytes(&self) -> usize {
+        self.invalid_bytes
+    }
+
+    /// Lines completed by this chunk
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        let mut lines = Vec::new();
+        match self.encoding {
+            Encoding::Detecting => {
+                let mut head = std::mem::take(&mut self.head);
+                head.extend_from_slice(bytes);
+                // Short of a full BOM that could still become one
+                let boms: [&[u8]; 3] = [&UTF8_BOM, &UTF16_LE_BOM, &UTF16_BE_BOM];
+                if boms.iter().any(|bom| head.len() < bom.len() && bom.starts_with(&head)) {
+                    self.head = head;
+                    return lines;
+                }
+                let body = self.detect(&head);
+                lines = self.push(body);
+            }
+            Encoding::Utf8 => self.split(bytes, &mut lines),
+            Encoding::Utf16 { big_endian } => {
+                let text = self.decode_utf16(bytes, big_endian);
+                self.split(text.as_bytes(), &mut lines);
+            }
+        }
+        lines
+    }
+
+    /// The last line, when the input doesn't end with a line break
+    pub fn finish(&mut self) -> Option<String> {
+        if self.encoding == Encoding::Detecting {
+            let head = std::mem::take(&mut self.head);
+            self.encoding = Encoding::Utf8;
+            self.partial.extend_from_slice(&head);
+        }
+        // A code unit or surrogate pair cut off by the end of the input
+        if self.odd_byte.take().is_some() {
+            self.invalid_bytes += 1;
+        }
+        if self.high_surrogate.take().is_some() {
+            self.invalid_bytes += 2;
+            self.partial.extend_from_slice("\u{fffd}".as_bytes());
+        }
+        (!self.partial.is_empty()).then(|| self.take_line())
+    }
+
+    /// Pick the encoding and return the bytes after the BOM
+    fn detect<'a>(&mut self, head: &'a [u8]) -> &'a [u8] {
+        if let Some(body) = head.strip_prefix(&UTF16_LE_BOM) {
+            self.encoding = Encoding::Utf16 { big_endian: false };
+            body
+        } else if let Some(body) = head.strip_prefix(&UTF16_BE_BOM) {
+            self.encoding = Encoding::Utf16 { big_endian: true };
+            body
+        } else {
+            self.encoding = Encoding::Utf8;
+            head.strip_prefix(&UTF8_BOM).unwrap_or(head)
+        }
+    }
+
+    /// Split UTF-8 bytes on line breaks; "\n" and "\r" never occur inside a multi-byte
+    /// character, so splitting before decoding is safe
+    fn split(&mut self, bytes: &[u8], lines: &mut Vec<String>) {
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            if std::mem::take(&mut self.after_cr) && rest[0] == b'\n' {
+                rest = &rest[1..];
+                continue;
+            }
+            let Some(end) = rest.iter().position(|b| matches!(b, b'\n' | b'\r')) else {
+                self.partial.extend_from_slice(rest);
+                return;
+            };
+            self.partial.extend_from_slice(&rest[..end]);
+            lines.push(self.take_line());
+            self.after_cr = rest[end] == b'\r';
+            rest = &rest[end + 1..];
+        }
+    }
+
+    /// Decode the line being read, replacing each invalid sequence with U+FFFD
+    fn take_line(&mut self) -> String {
+        let line = std::mem::take(&mut self.partial);
+        match String::from_utf8(line) {
+            Ok(line) => line,
+            Err(e) => {
+                let bytes = e.into_bytes();
+                let mut line = String::with_capacity(bytes.len());
+                let mut rest = bytes.as_slice();
+                loop {
+                    match std::str::from_utf8(rest) {
+                        Ok(valid) => {
+                            line.push_str(valid);
+                            return line;
+                        }
+                        Err(e) => {
+                            let (valid, invalid) = rest.split_at(e.valid_up_to());
+                            line.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                            line.push('\u{fffd}');
+                            // A sequence cut off by the end of the line runs to the end
+                            let length = e.error_len().unwrap_or(invalid.len());
+                            self.invalid_bytes += length;
+                            rest = &invalid[length..];
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decode UTF-16 code units to UTF-8, holding a trailing odd byte or high surrogate
+    fn decode_utf16(&mut self, bytes: &[u8], big_endian: bool) -> String {
+        // Empty chunks come through while the decompressor holds output back
+        let Some((head, tail)) = bytes.split_first() else {
+            return String::new();
+        };
+        let mut units = Vec::with_capacity(bytes.len() / 2 + 2);
+        units.extend(self.high_surrogate.take());
+
+        let mut rest = bytes;
+        if let Some(first) = self.odd_byte.take() {
+            units.push(unit([first, *head], big_endian));
+            rest = tail;
+        }
+        let mut pairs = rest.chunks_exact(2);
+        units.extend(pairs.by_ref().map(|pair| unit([pair[0], pair[1]], big_endian)));
+        self.odd_byte = pairs.remainder().first().copied();
+
+        if units.last().is_some_and(|u| (0xd800..0xdc00).contains(u)) {
+            self.high_surrogate = units.pop();
+        }
+
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or_else(|_| {
+                self.invalid_bytes += 2;
+                '\u{fffd}'
+            }))
+            .collect()
+    }
+}
+
+fn unit(bytes: [u8; 2], big_endian: bool) -> u16 {
+    if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed the input in chunks of `size` bytes
+    fn decode(input: &[u8], size: usize) -> (Vec<String>, usize) {
+        let mut decoder = LineDecoder::default();
+        let mut lines: Vec<String> = input.chunks(size).flat_map(|chunk| decoder.push(chunk)).collect();
+        lines.extend(decoder.finish());
+        (lines, decoder.invalid_bytes())
+    }
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        let bom = if big_endian { UTF16_BE_BOM } else { UTF16_LE_BOM };
+        bom.into_iter()
+            .chain(text.encode_utf16().flat_map(|u| if big_endian { u.to_be_bytes() } else { u.to_le_bytes() }))
+            .collect()
+    }
+
+    #[test]
+    fn test_line_breaks_across_chunks() {
+        let input = "first\r\nsecond caf\u{e9}\rthird\n\nlast".as_bytes();
+        for size in [1, 2, 7, 100] {
+            assert_eq!(decode(input, size), (vec!["first".to_string(), "second caf\u{e9}".to_string(), "third".to_string(), String::new(), "last".to_string()], 0));
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_replaced_and_counted() {
+        let mut input = b"ok \xff\xfe end\n".to_vec();
+        // A 3-byte character cut short by the end of the line
+        input.extend_from_slice(b"caf\xe2\x82\n");
+        assert_eq!(decode(&input, 3), (vec!["ok \u{fffd}\u{fffd} end".to_string(), "caf\u{fffd}".to_string()], 4));
+    }
+
+    #[test]
+    fn test_byte_order_marks() {
+        let text = "ERROR \u{1f525} hot\r\nnext";
+        for size in [1, 3, 64] {
+            for big_endian in [false, true] {
+                assert_eq!(decode(&utf16(text, big_endian), size).0, vec!["ERROR \u{1f525} hot", "next"]);
+            }
+            let mut utf8 = UTF8_BOM.to_vec();
+            utf8.extend_from_slice(text.as_bytes());
+            assert_eq!(decode(&utf8, size).0, vec!["ERROR \u{1f525} hot", "next"]);
+        }
+    }
+
+    #[test]
+    fn test_empty_chunk_inside_a_surrogate_pair() {
+        let bytes = utf16("\u{1f525}", false);
+        let mut decoder = LineDecoder::default();
+        // The BOM and the high surrogate, then one byte of the low surrogate
+        let mut lines = decoder.push(&bytes[..5]);
+        lines.extend(decoder.push(&[]));
+        lines.extend(decoder.push(&bytes[5..]));
+        lines.extend(decoder.finish());
+        assert_eq!((lines, decoder.invalid_bytes()), (vec!["\u{1f525}".to_string()], 0));
+    }
+
+    #[test]
+    fn test_unpaired_surrogates_are_counted() {
+        let mut input = UTF16_LE_BOM.to_vec();
+        input.extend_from_slice(&[b'a', 0, 0x00, 0xdc, b'b', 0, 0x3d, 0xd8]);
+        assert_eq!(decode(&input, 1), (vec!["a\u{fffd}b\u{fffd}".to_string()], 4));
+    }
+}
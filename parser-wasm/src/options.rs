@@ -0,0 +1,104 @@
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::JsValue;
+
+use crate::formats::json::JsonFieldPaths;
+use crate::framing::FramingRule;
+use crate::timestamps::TimeRange;
+
+// ============================================================================
+// PARSER OPTIONS
+// ============================================================================
+
+/// Options accepted by `parse_log` and the `LogParser` constructor
+/// Every field is optional on the JS side - missing fields use the defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParserOptions {
+    /// Field paths used to decode JSON Lines records
+    pub json_fields: JsonFieldPaths,
+    /// Force a format by name ("json", "logfmt", "syslog", "text") instead of auto-detection
+    pub format: Option<String>,
+    /// Number of leading lines sampled for format detection
+    pub detect_lines: usize,
+    /// How physical lines are grouped into records before classification
+    pub framing: FramingRule,
+    /// Upper bound on lines per record, so a missing start line can't swallow the log
+    /// Large enough for goroutine dumps, which run to thousands of lines
+    pub max_record_lines: usize,
+    /// Fingerprint and rank errors by their root (deepest) cause instead of the outer wrapper
+    pub group_by_root_cause: bool,
+    /// File path or module prefixes of the application's own code ("/srv/app/src", "com.acme.")
+    /// Matching frames are in-app even where the default vendor rules say otherwise
+    pub in_app_prefixes: Vec<String>,
+    /// Strip ANSI escape sequences and control characters before classification
+    /// full_trace keeps the lines as logged either way
+    pub strip_control_sequences: bool,
+    /// UTC offset for timestamps that don't carry one ("+02:00", "-0500", "Z"); UTC when unset
+    pub default_timezone: Option<String>,
+    /// Year for timestamps without one (syslog); when unset, the current year, or the
+    /// previous one for dates that would otherwise lie in the future
+    pub default_year: Option<i32>,
+    /// Occurrence timestamps kept per error group (the most recent ones); 0 keeps none
+    pub max_occurrence_timestamps: usize,
+    /// Histogram bucket width in seconds; when unset, widened automatically to fit histogram_max_buckets
+    pub histogram_bucket_seconds: Option<u64>,
+    /// Upper bound on histogram buckets; a configured width is doubled until the log fits
+    pub histogram_max_buckets: usize,
+    /// Bursts need a bucket this many times busier than the usual (median) one...
+    pub burst_factor: f64,
+    /// ...and at least this many occurrences in it, so a quiet log's single errors aren't bursts
+    pub burst_min_count: u32,
+    /// Upper bound on buckets for burst detection; finer than the histogram so bursts are
+    /// placed to the minute over a day's log
+    pub burst_max_buckets: usize,
+    /// Only analyse records whose timestamp falls in `[from, to)`; the rest are counted in
+    /// LogStats.out_of_range_lines. Records without a timestamp take the previous record's
+    pub time_range: Option<TimeRange>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            json_fields: JsonFieldPaths::default(),
+            format: None,
+            detect_lines: 50,
+            framing: FramingRule::Auto,
+            max_record_lines: 10_000,
+            group_by_root_cause: false,
+            in_app_prefixes: Vec::new(),
+            strip_control_sequences: true,
+            default_timezone: None,
+            default_year: None,
+            max_occurrence_timestamps: 100,
+            histogram_bucket_seconds: None,
+            histogram_max_buckets: 60,
+            burst_factor: 3.0,
+            burst_min_count: 5,
+            burst_max_buckets: 1440,
+            time_range: None,
+        }
+    }
+}
+
+impl ParserOptions {
+    /// Read options passed from JS
+    /// `undefined` / `null` yield the defaults; one invalid field is reported and discards
+    /// the whole object, so the parse runs on the defaults rather than a mix
+    pub fn from_js(value: Option<JsValue>) -> ParserOptions {
+        let value = match value {
+            Some(v) if !v.is_undefined() && !v.is_null() => v,
+            _ => return ParserOptions::default(),
+        };
+
+        match serde_wasm_bindgen::from_value::<ParserOptions>(value) {
+            Ok(options) => options,
+            Err(e) => {
+                crate::console_error(&format!(
+                    "Invalid parser options, ignoring all of them (format, framing, time_range, ...) and using the defaults: {}",
+                    e
+                ));
+                ParserOptions::default()
+            }
+        }
+    }
+}
@@ -105,11 +105,11 @@ pub fn levenshtein_distance(a: &str, b: &str) -> usize {
     let mut dp = vec![vec![0; n + 1]; m + 1];
 
     // Initialize first row and column
-    for i in 0..=m {
-        dp[i][0] = i;
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i;
     }
-    for j in 0..=n {
-        dp[0][j] = j;
+    for (j, cell) in dp[0].iter_mut().enumerate() {
+        *cell = j;
     }
 
     // Fill DP table
@@ -200,7 +200,6 @@ fn build_template_from_lcs(lcs: &str, example: &str) -> String {
         return "{VAR}".to_string();
     }
 
-    let mut template = example.to_string();
     let lcs_chars: Vec<char> = lcs.chars().collect();
     let example_chars: Vec<char> = example.chars().collect();
 
@@ -326,7 +325,7 @@ fn calculate_confidence(examples: &[String], lcs: &str) -> f32 {
     let confidence = (lcs_coverage * 0.5 + avg_similarity * 0.5) * length_penalty;
 
     // Clamp to [0, 1]
-    confidence.min(1.0).max(0.0)
+    confidence.clamp(0.0, 1.0)
 }
 
 // ============================================================================
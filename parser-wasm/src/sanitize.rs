@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+
+// ============================================================================
+// CONTROL SEQUENCES
+// ============================================================================
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{07}';
+/// 8-bit forms of ESC [ and ESC ]
+const CSI: char = '\u{9b}';
+const OSC: char = '\u{9d}';
+
+/// Remove ANSI escape sequences (SGR colours, cursor movement, OSC titles and
+/// hyperlinks) and other control characters; tabs are kept
+/// Lines without any are returned as-is
+pub fn strip_control_sequences(line: &str) -> Cow<'_, str> {
+    if !line.chars().any(|c| c != '\t' && c.is_control()) {
+        return Cow::Borrowed(line);
+    }
+
+    let mut clean = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\t' => clean.push(c),
+            ESC => match chars.next() {
+                Some('[') => skip_csi(&mut chars),
+                Some(']') => skip_osc(&mut chars),
+                // Two-byte sequences, some with intermediates: ESC ( B selects a character set
+                Some(c) if ('\u{20}'..='\u{2f}').contains(&c) => {
+                    while chars.next_if(|c| ('\u{20}'..='\u{2f}').contains(c)).is_some() {}
+                    chars.next();
+                }
+                _ => {}
+            },
+            CSI => skip_csi(&mut chars),
+            OSC => skip_osc(&mut chars),
+            c if c.is_control() => {}
+            c => clean.push(c),
+        }
+    }
+
+    Cow::Owned(clean)
+}
+
+/// Parameters and intermediates, then one final byte: ESC [ 1 ; 31 m
+fn skip_csi(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.next_if(|c| ('\u{20}'..='\u{3f}').contains(c)).is_some() {}
+    chars.next_if(|c| ('\u{40}'..='\u{7e}').contains(c));
+}
+
+/// Terminated by BEL or ST (ESC \): ESC ] 8 ; ; https://example.com BEL
+fn skip_osc(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while let Some(c) = chars.next() {
+        if c == BEL {
+            return;
+        }
+        if c == ESC {
+            chars.next_if_eq(&'\\');
+            return;
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_colours_and_cursor_sequences() {
+        assert_eq!(strip_control_sequences("\x1b[1;31mERROR\x1b[0m db down\x1b[K"), "ERROR db down");
+        assert_eq!(strip_control_sequences("\x1b(Bplain\x07"), "plain");
+    }
+
+    #[test]
+    fn test_strips_hyperlinks() {
+        let line = "see \x1b]8;;https://ci.acme.com/1\x1b\\build 1\x1b]8;;\x1b\\ failed";
+        assert_eq!(strip_control_sequences(line), "see build 1 failed");
+    }
+
+    #[test]
+    fn test_clean_lines_are_borrowed() {
+        assert!(matches!(strip_control_sequences("ERROR\tdb down"), Cow::Borrowed(_)));
+    }
+}
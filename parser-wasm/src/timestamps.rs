@@ -0,0 +1,285 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use regex::Regex;
+use lazy_static::lazy_static;
+use serde::{Serialize, Deserialize};
+
+use crate::options::ParserOptions;
+
+lazy_static! {
+    // 2026-10-16T23:05:01Z / 2026-10-16 23:05:01.123456+02:00 / 2026-10-16 23:05:01,120 (Python logging)
+    static ref ISO: Regex = Regex::new(
+        r"(\d{4})-(\d{2})-(\d{2})[T ](\d{2}):(\d{2}):(\d{2})(?:[.,](\d{1,9}))?(?: ?(Z|[+-]\d{2}:?\d{2}|UTC|GMT)\b)?"
+    ).unwrap();
+
+    // [16/Oct/2026:23:05:01 +0000]
+    static ref APACHE: Regex = Regex::new(
+        r"\[(\d{2})/([A-Z][a-z]{2})/(\d{4}):(\d{2}):(\d{2}):(\d{2})(?: ([+-]\d{4}))?\]"
+    ).unwrap();
+
+    // Oct 16 23:05:01 / Oct  6 23:05:01.123 (no year)
+    static ref SYSLOG: Regex = Regex::new(
+        r"\b([A-Z][a-z]{2}) {1,2}(\d{1,2}) (\d{2}):(\d{2}):(\d{2})(?:\.(\d{1,9}))?\b"
+    ).unwrap();
+
+    // 1760655901 / 1760655901.123 / 1760655901000 (ms) / 1760655901000000000 (ns) at the start of a line
+    static ref EPOCH_PREFIX: Regex = Regex::new(
+        r"^\[?(\d{10}(?:\.\d{1,9})?|\d{13}|\d{16}|\d{19})\b"
+    ).unwrap();
+
+    // "+02:00" / "-0500" / "Z" / "UTC"
+    static ref OFFSET: Regex = Regex::new(
+        r"^([+-])(\d{2}):?(\d{2})$"
+    ).unwrap();
+}
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Turns the timestamps of many log layouts into instants
+/// Offsets and years the layout leaves out come from the parser options
+#[derive(Debug, Clone)]
+pub struct TimestampParser {
+    offset: FixedOffset,
+    /// None: the current year, or the previous one for dates that would lie in the future
+    year: Option<i32>,
+    now: DateTime<Utc>,
+}
+
+/// `[from, to)` range of the records to analyse; either end may be left open
+/// Ends are read like record timestamps: RFC 3339, any layout `find` knows, or epoch numbers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// A TimeRange with its ends parsed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstantRange {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+impl TimestampParser {
+    /// Build from the options; an invalid default timezone is returned as an error message
+    pub fn new(options: &ParserOptions) -> Result<TimestampParser, String> {
+        let offset = match options.default_timezone.as_deref() {
+            None => FixedOffset::east_opt(0).unwrap(),
+            Some(timezone) => parse_offset(timezone)
+                .ok_or_else(|| format!("Invalid default timezone '{}', expected an offset like +02:00", timezone))?,
+        };
+
+        Ok(TimestampParser {
+            offset,
+            year: options.default_year,
+            now: Utc::now(),
+        })
+    }
+
+    /// First timestamp in a log line
+    pub fn find(&self, line: &str) -> Option<DateTime<Utc>> {
+        if let Some(caps) = ISO.captures(line) {
+            let date = NaiveDate::from_ymd_opt(caps[1].parse().ok()?, caps[2].parse().ok()?, caps[3].parse().ok()?)?;
+            let time = date.and_hms_nano_opt(caps[4].parse().ok()?, caps[5].parse().ok()?, caps[6].parse().ok()?, nanos(caps.get(7))?)?;
+            let offset = match caps.get(8) {
+                Some(offset) => parse_offset(offset.as_str())?,
+                None => self.offset,
+            };
+            return self.localize(time, offset);
+        }
+
+        if let Some(caps) = APACHE.captures(line) {
+            let date = NaiveDate::from_ymd_opt(caps[3].parse().ok()?, month(&caps[2])?, caps[1].parse().ok()?)?;
+            let time = date.and_hms_opt(caps[4].parse().ok()?, caps[5].parse().ok()?, caps[6].parse().ok()?)?;
+            let offset = match caps.get(7) {
+                Some(offset) => parse_offset(offset.as_str())?,
+                None => self.offset,
+            };
+            return self.localize(time, offset);
+        }
+
+        if let Some(caps) = SYSLOG.captures(line) {
+            let (month, day) = (month(&caps[1])?, caps[2].parse().ok()?);
+            let (hour, minute, second) = (caps[3].parse().ok()?, caps[4].parse().ok()?, caps[5].parse().ok()?);
+            let at_year = |year: i32| {
+                NaiveDate::from_ymd_opt(year, month, day)?.and_hms_nano_opt(hour, minute, second, nanos(caps.get(6))?)
+            };
+
+            if let Some(year) = self.year {
+                return self.localize(at_year(year)?, self.offset);
+            }
+            // A December log read in January belongs to last year
+            let year = self.now.year();
+            let instant = self.localize(at_year(year)?, self.offset)?;
+            if instant > self.now + chrono::Duration::days(1) {
+                return self.localize(at_year(year - 1)?, self.offset);
+            }
+            return Some(instant);
+        }
+
+        EPOCH_PREFIX.captures(line).and_then(|caps| parse_epoch(&caps[1]))
+    }
+
+    /// Timestamp field of a structured record: epoch numbers or any layout `find` knows
+    pub fn parse(&self, value: &str) -> Option<DateTime<Utc>> {
+        let value = value.trim();
+        if value.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            return parse_epoch(value);
+        }
+        self.find(value)
+    }
+
+    /// Parse the ends of a range; unreadable or inverted ends are returned as an error message
+    pub fn range(&self, range: &TimeRange) -> Result<InstantRange, String> {
+        let end = |value: &Option<String>| match value {
+            Some(value) => self.parse(value).map(Some).ok_or_else(|| format!("Invalid time range end '{}'", value)),
+            None => Ok(None),
+        };
+        let (from, to) = (end(&range.from)?, end(&range.to)?);
+        if let (Some(from), Some(to)) = (from, to) {
+            if from >= to {
+                return Err(format!("Empty time range: {} is not before {}", format_timestamp(&from), format_timestamp(&to)));
+            }
+        }
+        Ok(InstantRange { from, to })
+    }
+
+    fn localize(&self, time: NaiveDateTime, offset: FixedOffset) -> Option<DateTime<Utc>> {
+        offset.from_local_datetime(&time).single().map(|t| t.with_timezone(&Utc))
+    }
+}
+
+impl InstantRange {
+    pub fn contains(&self, instant: &DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| *instant >= from) && self.to.is_none_or(|to| *instant < to)
+    }
+}
+
+/// Sortable RFC 3339 form used in results: 2026-10-16T23:05:01.120Z
+pub fn format_timestamp(instant: &DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_offset(offset: &str) -> Option<FixedOffset> {
+    if matches!(offset, "Z" | "UTC" | "GMT") {
+        return FixedOffset::east_opt(0);
+    }
+    let caps = OFFSET.captures(offset)?;
+    let seconds = caps[2].parse::<i32>().ok()? * 3600 + caps[3].parse::<i32>().ok()? * 60;
+    FixedOffset::east_opt(if &caps[1] == "-" { -seconds } else { seconds })
+}
+
+/// Seconds, milliseconds, microseconds or nanoseconds, told apart by their digit count
+fn parse_epoch(value: &str) -> Option<DateTime<Utc>> {
+    if let Some((seconds, fraction)) = value.split_once('.') {
+        let nanos = format!("{:0<9}", fraction).get(..9)?.parse().ok()?;
+        return DateTime::from_timestamp(seconds.parse().ok()?, nanos);
+    }
+
+    let number: i64 = value.parse().ok()?;
+    match value.len() {
+        ..=11 => DateTime::from_timestamp(number, 0),
+        12..=14 => DateTime::from_timestamp_millis(number),
+        15..=17 => DateTime::from_timestamp_micros(number),
+        _ => Some(DateTime::from_timestamp_nanos(number)),
+    }
+}
+
+/// ".1" is 100ms, ".123456" is 123456µs
+fn nanos(fraction: Option<regex::Match>) -> Option<u32> {
+    match fraction {
+        Some(m) => format!("{:0<9}", m.as_str()).parse().ok(),
+        None => Some(0),
+    }
+}
+
+fn month(name: &str) -> Option<u32> {
+    MONTHS.iter().position(|m| *m == name).map(|i| i as u32 + 1)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser(timezone: Option<&str>, year: Option<i32>) -> TimestampParser {
+        TimestampParser::new(&ParserOptions {
+            default_timezone: timezone.map(str::to_string),
+            default_year: year,
+            ..ParserOptions::default()
+        }).unwrap()
+    }
+
+    fn find(parser: &TimestampParser, line: &str) -> Option<String> {
+        parser.find(line).map(|t| format_timestamp(&t))
+    }
+
+    #[test]
+    fn test_layouts() {
+        let parser = parser(None, Some(2026));
+        let expected = Some("2026-10-16T23:05:01.120Z".to_string());
+
+        assert_eq!(find(&parser, "2026-10-16T23:05:01.120Z ERROR x"), expected);
+        assert_eq!(find(&parser, "2026-10-17T01:05:01.12+02:00 ERROR x"), expected);
+        assert_eq!(find(&parser, "2026-10-16 23:05:01,120 ERROR x"), expected);
+        assert_eq!(find(&parser, "Oct 16 23:05:01.120 web-1 app[42]: x"), expected);
+        assert_eq!(find(&parser, "1760655901.120 ERROR x").as_deref(), Some("2025-10-16T23:05:01.120Z"));
+        assert_eq!(find(&parser, r#"10.0.0.1 - - [16/Oct/2026:19:05:01 -0400] "GET / HTTP/1.1" 500"#).as_deref(), Some("2026-10-16T23:05:01.000Z"));
+        assert_eq!(find(&parser, "no timestamp here"), None);
+    }
+
+    #[test]
+    fn test_epoch_fields() {
+        let parser = parser(None, None);
+        for value in ["1760655901", "1760655901000", "1760655901000000", "1760655901000000000"] {
+            assert_eq!(parser.parse(value).map(|t| format_timestamp(&t)).as_deref(), Some("2025-10-16T23:05:01.000Z"), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_defaults_apply_to_incomplete_layouts() {
+        let parser = parser(Some("+02:00"), Some(2024));
+        assert_eq!(find(&parser, "2026-10-16 23:05:01 ERROR x").as_deref(), Some("2026-10-16T21:05:01.000Z"));
+        assert_eq!(find(&parser, "Oct 16 23:05:01 web-1 x").as_deref(), Some("2024-10-16T21:05:01.000Z"));
+        // Explicit offsets win over the default
+        assert_eq!(find(&parser, "2026-10-16 23:05:01Z ERROR x").as_deref(), Some("2026-10-16T23:05:01.000Z"));
+    }
+
+    #[test]
+    fn test_range_is_half_open() {
+        let parser = parser(Some("+02:00"), None);
+        let range = parser.range(&TimeRange {
+            from: Some("2026-10-17 01:00:00".to_string()),
+            to: Some("2026-10-16T23:20:00Z".to_string()),
+        }).unwrap();
+
+        assert!(range.contains(&parser.parse("2026-10-16T23:00:00Z").unwrap()));
+        assert!(range.contains(&parser.parse("2026-10-16T23:19:59.999Z").unwrap()));
+        assert!(!range.contains(&parser.parse("2026-10-16T23:20:00Z").unwrap()));
+        assert!(!range.contains(&parser.parse("2026-10-16T22:59:59Z").unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_ranges_are_rejected() {
+        let parser = parser(None, None);
+        let range = |from: &str, to: &str| parser.range(&TimeRange { from: Some(from.to_string()), to: Some(to.to_string()) });
+        assert!(range("yesterday", "2026-10-16T23:20:00Z").is_err());
+        assert!(range("2026-10-16T23:20:00Z", "2026-10-16T23:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_invalid_timezone_is_rejected() {
+        let options = ParserOptions {
+            default_timezone: Some("Europe/Paris".to_string()),
+            ..ParserOptions::default()
+        };
+        assert!(TimestampParser::new(&options).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{split_qualified, CauseKind, DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // System.NullReferenceException: Object reference not set to an instance of an object.
+    static ref DOTNET_HEADER: Regex = Regex::new(
+        r"^((?:[A-Za-z_]\w*\.)+[A-Za-z_]\w*)(?::\s?(.*))?$"
+    ).unwrap();
+
+    // at Orders.Service.Load(Int32 id) in /src/Orders/Service.cs:line 42 / at System.ThrowHelper.Throw()
+    static ref DOTNET_FRAME: Regex = Regex::new(
+        r"^\s*at (.+?)\(([^()]*)\)(?: in (.+):line (\d+))?\s*$"
+    ).unwrap();
+
+    // Typed parameter lists never appear in JVM frames: "Int32 id, String name"
+    static ref TYPED_PARAMETERS: Regex = Regex::new(
+        r"^[\w.`\[\]<>&*]+ \w+(?:, [\w.`\[\]<>&*]+ \w+)*$"
+    ).unwrap();
+
+    // Orders.Service.<LoadAsync>d__5.MoveNext -> Orders.Service.LoadAsync
+    static ref ASYNC_STATE_MACHINE: Regex = Regex::new(
+        r"^(.*)\.<([^>]+)>d__\d+\.MoveNext$"
+    ).unwrap();
+}
+
+const INNER_ARROW: &str = "--->";
+const END_OF_INNER: &str = "--- End of inner exception stack trace ---";
+const END_OF_ASYNC: &str = "--- End of stack trace from previous location";
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a .NET `Exception.ToString()` dump
+/// Inner exceptions (`--->`) become causes; their frames are printed first, each
+/// block closed by "--- End of inner exception stack trace ---"
+/// Returns None unless the record has .NET-only syntax, so JVM traces are left to the Java decoder
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let start = lines.iter().position(|l| DOTNET_HEADER.is_match(l))?;
+    let lines = &lines[start..];
+    if !lines.iter().any(|l| is_dotnet_line(l)) {
+        return None;
+    }
+
+    let mut chain: Vec<DecodedException> = Vec::new();
+    let mut segments: Vec<Vec<StackFrame>> = vec![Vec::new()];
+
+    for line in lines {
+        let trimmed = line.trim();
+
+        if trimmed == END_OF_INNER {
+            segments.push(Vec::new());
+            continue;
+        }
+        // Async rethrow boundary - the frames below still belong to the same exception
+        if trimmed.starts_with(END_OF_ASYNC) {
+            continue;
+        }
+        if let Some(caps) = DOTNET_FRAME.captures(line) {
+            segments.last_mut()?.push(frame_from_captures(line, &caps));
+            continue;
+        }
+
+        // .NET Framework prints the whole chain on one line, .NET Core puts each " ---> " on its own
+        if chain.is_empty() || trimmed.starts_with(INNER_ARROW) {
+            for part in trimmed.trim_start_matches(INNER_ARROW).split(" ---> ") {
+                chain.extend(decode_header(part));
+            }
+        } else if DOTNET_HEADER.is_match(line) {
+            // Another exception; anything else is a multi-line message and is skipped
+            break;
+        }
+    }
+
+    // Frame blocks run from the innermost exception out to the one that was thrown
+    let depth = chain.len();
+    for (i, frames) in segments.into_iter().enumerate().take(depth) {
+        chain[depth - 1 - i].frames = frames;
+    }
+
+    let mut links = chain.into_iter().map(|mut exception| {
+        // Frames without "in file:line" have no symbols (framework code) - attribute to the first with a file
+        exception.culprit = exception.frames.iter().position(|f| f.file.is_some()).unwrap_or(0);
+        exception
+    });
+    let mut exception = links.next()?;
+    exception.causes = links.map(|cause| cause.into_cause(CauseKind::Cause)).collect();
+    Some(exception)
+}
+
+/// Whether a line uses syntax only .NET prints
+fn is_dotnet_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    if trimmed.starts_with(INNER_ARROW) || trimmed.contains(" ---> ") || trimmed.starts_with("--- End of ") {
+        return true;
+    }
+
+    DOTNET_FRAME.captures(line).is_some_and(|caps| {
+        let parameters = &caps[2];
+        caps.get(3).is_some() ||
+            parameters.is_empty() ||
+            (TYPED_PARAMETERS.is_match(parameters) && parameters != "Unknown Source" && parameters != "Native Method")
+    })
+}
+
+fn decode_header(part: &str) -> Option<DecodedException> {
+    // AggregateException: " ---> (Inner Exception #0) System.Exception: ..."
+    let part = part.trim();
+    let part = match part.strip_prefix("(Inner Exception #") {
+        Some(rest) => rest.split_once(") ").map_or(rest, |(_, header)| header),
+        None => part,
+    };
+
+    let caps = DOTNET_HEADER.captures(part)?;
+    Some(DecodedException {
+        exception_type: Some(caps[1].to_string()),
+        message: caps.get(2).map(|m| m.as_str().trim().to_string()).unwrap_or_default(),
+        ..DecodedException::default()
+    })
+}
+
+/// Parse a frame line that only .NET prints (see `is_dotnet_line`)
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    if !is_dotnet_line(line) {
+        return None;
+    }
+    DOTNET_FRAME.captures(line).map(|caps| frame_from_captures(line, &caps))
+}
+
+fn frame_from_captures(line: &str, caps: &regex::Captures) -> StackFrame {
+    let name = caps[1].trim();
+    let name = match ASYNC_STATE_MACHINE.captures(name) {
+        Some(parts) => format!("{}.{}", &parts[1], &parts[2]),
+        None => name.to_string(),
+    };
+
+    // Constructors keep their leading dot: "Acme.Order..ctor" -> ("Acme.Order", ".ctor")
+    let (module, function) = match name.rsplit_once("..") {
+        Some((module, ctor)) => (Some(module.to_string()), Some(format!(".{}", ctor))),
+        None => split_qualified(&name, "."),
+    };
+
+    StackFrame {
+        function,
+        module,
+        file: caps.get(3).map(|m| m.as_str().to_string()),
+        line: caps.get(4).and_then(|m| m.as_str().parse().ok()),
+        column: None,
+        language: Some(Language::Dotnet),
+        raw: line.trim().to_string(),
+        ..StackFrame::default()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_decode_inner_exceptions() {
+        let exception = decode(&lines("System.InvalidOperationException: Failed to load order 42
+ ---> System.IO.IOException: Disk full
+   at Orders.Storage.Write(Byte[] data) in /src/Orders/Storage.cs:line 10
+   --- End of inner exception stack trace ---
+   at Orders.Service.<LoadAsync>d__5.MoveNext() in /src/Orders/Service.cs:line 57
+--- End of stack trace from previous location ---
+   at System.Runtime.ExceptionServices.ExceptionDispatchInfo.Throw()
+   at Orders.Controller.Get(Int32 id) in /src/Orders/Controller.cs:line 15")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("System.InvalidOperationException"));
+        assert_eq!(exception.message, "Failed to load order 42");
+        assert_eq!(exception.frames.len(), 3);
+        assert_eq!(exception.frames[0].module.as_deref(), Some("Orders.Service"));
+        assert_eq!(exception.frames[0].function.as_deref(), Some("LoadAsync"));
+        assert_eq!(exception.location(), (Some("/src/Orders/Service.cs".to_string()), Some(57), None));
+
+        assert_eq!(exception.causes.len(), 1);
+        let cause = &exception.causes[0];
+        assert_eq!(cause.exception_type.as_deref(), Some("System.IO.IOException"));
+        assert_eq!(cause.message, "Disk full");
+        assert_eq!(cause.file.as_deref(), Some("/src/Orders/Storage.cs"));
+        assert_eq!(cause.line, Some(10));
+    }
+
+    #[test]
+    fn test_framework_single_line_chain() {
+        let exception = decode(&lines("System.Exception: outer ---> System.ArgumentNullException: Value cannot be null.
+   at Acme.Parse(String s)
+   --- End of inner exception stack trace ---
+   at Acme.Run()")).unwrap();
+
+        assert_eq!(exception.message, "outer");
+        assert_eq!(exception.frames[0].function.as_deref(), Some("Run"));
+        assert_eq!(exception.causes[0].exception_type.as_deref(), Some("System.ArgumentNullException"));
+        assert_eq!(exception.causes[0].frames[0].module.as_deref(), Some("Acme"));
+    }
+
+    #[test]
+    fn test_java_traces_are_left_alone() {
+        assert!(decode(&lines("java.lang.IllegalStateException: closed
+\tat com.acme.Pool.get(Pool.java:12)
+\tat java.base/java.lang.Thread.run(Unknown Source)")).is_none());
+    }
+}
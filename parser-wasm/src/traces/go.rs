@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, HashMap};
+
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // panic: runtime error: index out of range [5] with length 3 / fatal error: concurrent map writes
+    static ref GO_PANIC: Regex = Regex::new(
+        r"^(panic|fatal error): (.*)$"
+    ).unwrap();
+
+    // goroutine 1 [running]: / goroutine 18 [chan receive, 5 minutes]: / goroutine 1 gp=0xc000002380 m=0 mp=0x5c1e40 [running]:
+    static ref GOROUTINE_HEADER: Regex = Regex::new(
+        r"^goroutine (\d+)(?: gp=\S+ m=\S+(?: mp=\S+)?)? \[([^\]]*)\]:$"
+    ).unwrap();
+
+    // main.(*Server).handle(0x0, {0x5c1e40, 0xc000010000}) / panic({0x6a8b20?, 0x9c7e50?})
+    static ref GO_FUNCTION: Regex = Regex::new(
+        r"^[\w./-]+(?:\.\(\*?[\w\[\].]+\))?(?:\.[\w\[\].-]+)*\(.*\)$"
+    ).unwrap();
+
+    // \t/app/server.go:42 +0x1c
+    static ref GO_FILE: Regex = Regex::new(
+        r"^\s+(\S+\.go):(\d+)(?:\s+\+0x[0-9a-f]+)?\s*$"
+    ).unwrap();
+
+    // [signal SIGSEGV: segmentation violation code=0x1 addr=0x0 pc=0x4a2b3c]
+    static ref GO_SIGNAL: Regex = Regex::new(
+        r"^\[signal (SIG\w+: [^\]]+?)(?: code=.*)?\]$"
+    ).unwrap();
+
+    // /usr/local/go/src/ / /usr/lib/go-1.22/src/ / /opt/homebrew/Cellar/go/1.22.1/libexec/src/
+    static ref GOROOT_SRC: Regex = Regex::new(
+        r"/(?:go[\w.-]*|libexec)/src/"
+    ).unwrap();
+
+    // SIGQUIT: quit / PC=0x46c9c1 m=0 sigcode=0 / rax    0xca
+    static ref SIGNAL_DUMP: Regex = Regex::new(
+        r"^(?:SIG[A-Z]+: \w+|PC=0x[0-9a-f]+ .*|[a-z0-9]{2,4}\s+0x[0-9a-f]+)$"
+    ).unwrap();
+}
+
+/// One `goroutine N [state]:` block
+struct Goroutine {
+    id: String,
+    state: String,
+    frames: Vec<StackFrame>,
+}
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether `line` belongs to the Go crash dump being framed in `pending`
+/// Function lines are unindented and goroutine blocks are separated by blank lines,
+/// so the generic stack trace rule would split the dump apart
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    let Some(first) = pending.first() else {
+        return false;
+    };
+    if !GO_PANIC.is_match(first) && !GOROUTINE_HEADER.is_match(first) && !SIGNAL_DUMP.is_match(first) {
+        return false;
+    }
+
+    let trimmed = line.trim();
+    trimmed.is_empty() ||
+        line.starts_with(char::is_whitespace) ||
+        GOROUTINE_HEADER.is_match(line) ||
+        GO_FUNCTION.is_match(line) ||
+        GO_SIGNAL.is_match(line) ||
+        SIGNAL_DUMP.is_match(line) ||
+        trimmed.starts_with("created by ") ||
+        trimmed.starts_with("exit status ") ||
+        trimmed == "runtime stack:" ||
+        trimmed == "...additional frames elided..."
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a Go `panic:` / `fatal error:` dump
+/// The first goroutine is the one that panicked; the others are summarised by state
+/// in the attributes, which is what matters when debugging a deadlock
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let start = lines.iter().position(|l| GO_PANIC.is_match(l))?;
+    let caps = GO_PANIC.captures(&lines[start])?;
+
+    let mut goroutines = parse_goroutines(&lines[start + 1..]);
+    if goroutines.is_empty() {
+        return None;
+    }
+    let others = goroutines.split_off(1);
+    let panicking = goroutines.pop()?;
+
+    let mut attributes = BTreeMap::new();
+    attributes.insert("goroutine".to_string(), panicking.id);
+    attributes.insert("goroutines".to_string(), (others.len() + 1).to_string());
+    if !others.is_empty() {
+        attributes.insert("goroutine_states".to_string(), summarize_states(&others));
+    }
+    if let Some(signal) = lines.iter().find_map(|l| GO_SIGNAL.captures(l)) {
+        attributes.insert("signal".to_string(), signal[1].to_string());
+    }
+
+    // Errors are attributed to the code that called into the runtime, not to runtime.gopark
+    let culprit = panicking.frames.iter().position(is_user_frame).unwrap_or(0);
+
+    Some(DecodedException {
+        exception_type: Some(caps[1].to_string()),
+        message: caps[2].trim().trim_end_matches(" [recovered]").to_string(),
+        frames: panicking.frames,
+        culprit,
+        fatal: true,
+        attributes,
+        ..DecodedException::default()
+    })
+}
+
+fn parse_goroutines(lines: &[String]) -> Vec<Goroutine> {
+    let mut goroutines: Vec<Goroutine> = Vec::new();
+    let mut function: Option<&str> = None;
+    let mut in_creator = false;
+
+    for line in lines {
+        if let Some(caps) = GOROUTINE_HEADER.captures(line) {
+            // "chan receive, 5 minutes" / "select, locked to thread" -> the state alone
+            let state = caps[2].split(',').next().unwrap_or_default().trim();
+            goroutines.push(Goroutine {
+                id: caps[1].to_string(),
+                state: state.to_string(),
+                frames: Vec::new(),
+            });
+            function = None;
+            continue;
+        }
+
+        let Some(goroutine) = goroutines.last_mut() else {
+            // "runtime stack:" frames before the first goroutine belong to the runtime itself
+            continue;
+        };
+
+        // "created by main.main in goroutine 1" names the spawning call, not a frame of this goroutine
+        if line.starts_with("created by ") {
+            in_creator = true;
+            function = None;
+        } else if let Some(caps) = GO_FILE.captures(line) {
+            if !std::mem::take(&mut in_creator) {
+                let function_line = function.take();
+                let (module, name) = function_line
+                    .map(|f| split_package(strip_arguments(f.trim())))
+                    .unwrap_or_default();
+                goroutine.frames.push(StackFrame {
+                    function: name,
+                    module,
+                    file: Some(caps[1].to_string()),
+                    line: caps[2].parse().ok(),
+                    column: None,
+                    language: Some(Language::Go),
+                    raw: match function_line {
+                        Some(f) => format!("{}\n{}", f.trim(), line.trim()),
+                        None => line.trim().to_string(),
+                    },
+                    ..StackFrame::default()
+                });
+            }
+        } else if GO_FUNCTION.is_match(line) {
+            function = Some(line);
+        }
+    }
+
+    goroutines
+}
+
+/// "400 chan receive, 2 select, 1 IO wait" - most common state first
+fn summarize_states(goroutines: &[Goroutine]) -> String {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for goroutine in goroutines {
+        *counts.entry(goroutine.state.as_str()).or_default() += 1;
+    }
+
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    counts.iter()
+        .map(|(state, count)| format!("{} {}", count, state))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// "main.(*Server).handle(0x0, {...})" -> "main.(*Server).handle"
+fn strip_arguments(function: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in function.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &function[..i];
+                }
+            }
+            _ => {}
+        }
+    }
+    function
+}
+
+/// Split "github.com/acme/api.(*Server).handle" into package and function
+/// The package runs up to the first '.' after the last '/'
+fn split_package(function: &str) -> (Option<String>, Option<String>) {
+    let slash = function.rfind('/').map_or(0, |i| i + 1);
+    match function[slash..].find('.') {
+        Some(dot) => (Some(function[..slash + dot].to_string()), Some(function[slash + dot + 1..].to_string())),
+        None => (None, Some(function.to_string())),
+    }
+}
+
+/// Frames outside the runtime, the standard library and module dependencies
+pub fn is_user_frame(frame: &StackFrame) -> bool {
+    // Builtins such as panic() have no package
+    let (Some(package), Some(file)) = (frame.module.as_deref(), frame.file.as_deref()) else {
+        return false;
+    };
+    if file.contains("/pkg/mod/") {
+        return false;
+    }
+
+    // Standard library packages have no domain and live under $GOROOT/src
+    // (runtime linkname functions such as sync.runtime_Semacquire sit in another package's dir)
+    let is_std = package != "main" &&
+                 !package.split('/').next().unwrap_or_default().contains('.') &&
+                 (file.contains(&format!("/src/{}/", package)) || GOROOT_SRC.is_match(file));
+    !is_std
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_decode_nil_pointer_panic() {
+        let exception = decode(&lines("panic: runtime error: invalid memory address or nil pointer dereference
+[signal SIGSEGV: segmentation violation code=0x1 addr=0x0 pc=0x4a2b3c]
+
+goroutine 7 [running]:
+panic({0x6a8b20?, 0x9c7e50?})
+\t/usr/local/go/src/runtime/panic.go:770 +0x132
+main.(*Server).handle(0x0, {0x5c1e40, 0xc000010000})
+\t/app/server.go:42 +0x1c
+net/http.HandlerFunc.ServeHTTP(0xc0000a8000, {0x7a8b10, 0xc000123440}, 0xc000124000)
+\t/usr/local/go/src/net/http/server.go:2171 +0x29
+created by net/http.(*Server).Serve in goroutine 1
+\t/usr/local/go/src/net/http/server.go:3285 +0x4b4")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("panic"));
+        assert_eq!(exception.message, "runtime error: invalid memory address or nil pointer dereference");
+        assert_eq!(exception.frames.len(), 3);
+        assert_eq!(exception.frames[1].module.as_deref(), Some("main"));
+        assert_eq!(exception.frames[1].function.as_deref(), Some("(*Server).handle"));
+        assert_eq!(exception.location(), (Some("/app/server.go".to_string()), Some(42), None));
+        assert_eq!(exception.attributes.get("signal").map(String::as_str), Some("SIGSEGV: segmentation violation"));
+        assert_eq!(exception.attributes.get("goroutine").map(String::as_str), Some("7"));
+        assert!(exception.fatal);
+    }
+
+    #[test]
+    fn test_deadlock_summarises_goroutines() {
+        let mut dump = String::from("fatal error: all goroutines are asleep - deadlock!
+
+goroutine 1 [semacquire]:
+sync.runtime_Semacquire(0xc000012345?)
+\t/usr/local/go/src/runtime/sema.go:62 +0x25
+sync.(*WaitGroup).Wait(0xc000012340?)
+\t/usr/local/go/src/sync/waitgroup.go:116 +0x48
+main.main()
+\t/app/main.go:31 +0x1a5
+");
+        for id in 2..5 {
+            dump.push_str(&format!("
+goroutine {} [chan receive, 5 minutes]:
+main.worker(0xc000020060)
+\t/app/worker.go:12 +0x3d
+created by main.main in goroutine 1
+\t/app/main.go:24 +0x95
+", id));
+        }
+        dump.push_str("\ngoroutine 9 [select]:\nmain.ticker()\n\t/app/main.go:50 +0x10\n");
+
+        let exception = decode(&lines(&dump)).unwrap();
+        assert_eq!(exception.exception_type.as_deref(), Some("fatal error"));
+        assert_eq!(exception.message, "all goroutines are asleep - deadlock!");
+        assert_eq!(exception.location(), (Some("/app/main.go".to_string()), Some(31), None));
+        assert_eq!(exception.attributes.get("goroutines").map(String::as_str), Some("5"));
+        assert_eq!(
+            exception.attributes.get("goroutine_states").map(String::as_str),
+            Some("3 chan receive, 1 select")
+        );
+    }
+
+    #[test]
+    fn test_dump_is_one_record() {
+        let dump = lines("panic: boom
+
+goroutine 1 [running]:
+main.main()
+\t/app/main.go:10 +0x25
+exit status 2");
+
+        for i in 1..dump.len() {
+            assert!(continues_record(&dump[i], &dump[..i]), "line {} split the dump", i);
+        }
+        assert!(!continues_record("2026-10-16 23:05:01 INFO restarted", &dump));
+        assert!(!continues_record("main.main()", &lines("INFO starting")));
+    }
+
+    #[test]
+    fn test_panic_without_goroutines_is_ignored() {
+        assert!(decode(&lines("panic: disk full")).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{go, native, Language, StackFrame};
+
+lazy_static! {
+    // CPython standard library: /usr/lib/python3.11/json/decoder.py / C:/Python311/Lib/json/decoder.py
+    static ref PYTHON_STDLIB: Regex = Regex::new(
+        r"/(?:lib/python\d+(?:\.\d+)?|Python\d+/Lib)/"
+    ).unwrap();
+}
+
+/// Installed dependencies, whatever the language
+const VENDOR_PATHS: [&str; 8] = [
+    "/node_modules/", "/site-packages/", "/dist-packages/", "/vendor/", "/gems/",
+    "/pkg/mod/", "/.cargo/registry/", "/.cargo/git/",
+];
+
+/// JDK, JVM languages and the frameworks most traces pass through
+const JVM_PACKAGES: [&str; 16] = [
+    "java.", "javax.", "jdk.", "sun.", "com.sun.", "kotlin.", "kotlinx.", "scala.", "groovy.",
+    "org.springframework.", "org.apache.", "org.hibernate.", "org.eclipse.jetty.", "io.netty.",
+    "com.fasterxml.", "reactor.",
+];
+
+/// Base class library and ASP.NET Core
+const DOTNET_NAMESPACES: [&str; 2] = ["System.", "Microsoft."];
+
+/// Rust standard library crates; dependencies are caught by their ~/.cargo path
+const RUST_CRATES: [&str; 3] = ["std::", "core::", "alloc::"];
+
+// ============================================================================
+// CLASSIFICATION
+// ============================================================================
+
+/// Set `in_app` on every frame
+/// Frames matching one of `in_app_prefixes` (file path or module) are always in-app;
+/// the rest are in-app unless a vendor rule for their language says otherwise
+/// Returns the index of the top in-app frame that has a file, which is where the error is reported
+pub fn classify(frames: &mut [StackFrame], in_app_prefixes: &[String]) -> Option<usize> {
+    for frame in frames.iter_mut() {
+        frame.in_app = matches_prefix(frame, in_app_prefixes) || !is_library(frame);
+    }
+    frames.iter().position(|f| f.in_app && f.file.is_some())
+}
+
+fn matches_prefix(frame: &StackFrame, prefixes: &[String]) -> bool {
+    prefixes.iter().any(|prefix| {
+        frame.file.as_deref().is_some_and(|file| file.starts_with(prefix.as_str())) ||
+            frame.module.as_deref().is_some_and(|module| module.starts_with(prefix.as_str()))
+    })
+}
+
+/// Dependencies, runtimes and standard libraries
+fn is_library(frame: &StackFrame) -> bool {
+    // Leading '/' so relative "node_modules/x.js" matches, forward slashes for Windows paths
+    let file = frame.file.as_deref().map(|file| format!("/{}", file.replace('\\', "/")));
+    let file = file.as_deref().unwrap_or_default();
+    let module = frame.module.as_deref().unwrap_or_default();
+
+    if VENDOR_PATHS.iter().any(|path| file.contains(path)) {
+        return true;
+    }
+
+    match frame.language {
+        Some(Language::Python) => PYTHON_STDLIB.is_match(file) || file.starts_with("/<frozen "),
+        Some(Language::Java) => JVM_PACKAGES.iter().any(|package| module.starts_with(package)),
+        // Node core modules: "node:internal/process/task_queues", or "internal/timers.js" before Node 16
+        Some(Language::Javascript) => file.starts_with("/node:") || file.starts_with("/internal/"),
+        Some(Language::Dotnet) => module == "System" || DOTNET_NAMESPACES.iter().any(|ns| module.starts_with(ns)),
+        Some(Language::Go) => !go::is_user_frame(frame),
+        Some(Language::Rust) => {
+            // "<alloc::boxed::Box<F,A> as core::ops::function::FnOnce<Args>>" trait impls count as their type
+            let module = module.trim_start_matches('<');
+            file.contains("/rustc/") || RUST_CRATES.iter().any(|krate| module.starts_with(krate))
+        }
+        Some(Language::Ruby) => file.contains("/lib/ruby/") || file.starts_with("/<internal:"),
+        // "[internal function]" frames have no file
+        Some(Language::Php) => frame.file.is_none(),
+        Some(Language::Native) => !native::is_app_frame(frame),
+        None => false,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::parse_frames;
+
+    #[test]
+    fn test_node_modules_are_skipped() {
+        let mut frames = parse_frames([
+            "    at Layer.handle [as handle_request] (/srv/app/node_modules/express/lib/router/layer.js:95:5)",
+            "    at processTicksAndRejections (node:internal/process/task_queues:95:5)",
+            "    at loadUser (/srv/app/src/users.js:12:11)",
+        ]);
+
+        assert_eq!(classify(&mut frames, &[]), Some(2));
+        assert_eq!(frames.iter().map(|f| f.in_app).collect::<Vec<_>>(), vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_language_rules() {
+        let frames = parse_frames([
+            r#"  File "/usr/lib/python3.11/json/decoder.py", line 355, in raw_decode"#,
+            r#"  File "/srv/app/.venv/lib/python3.11/site-packages/requests/api.py", line 59, in request"#,
+            "\tat java.base/java.lang.Thread.run(Thread.java:833)",
+            "   at System.Text.Json.JsonSerializer.Deserialize(String json) in /_/src/JsonSerializer.cs:line 12",
+            "/usr/lib/ruby/3.2.0/json/common.rb:216:in `parse'",
+            "#1 [internal function]: App\\Controller->show(5)",
+            "\tat com.acme.api.UserController.show(UserController.java:42)",
+        ]);
+
+        let library = frames.iter().map(is_library).collect::<Vec<_>>();
+        assert_eq!(library, vec![true, true, true, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_prefixes_override_vendor_rules() {
+        let mut frames = parse_frames([
+            "\tat org.apache.kafka.clients.Sender.run(Sender.java:10)",
+            "\tat com.acme.Worker.poll(Worker.java:20)",
+        ]);
+
+        assert_eq!(classify(&mut frames, &["org.apache.kafka.".to_string()]), Some(0));
+        assert!(frames.iter().all(|f| f.in_app));
+    }
+}
@@ -0,0 +1,151 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{split_qualified, CauseKind, DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // java.lang.IllegalStateException: message / com.acme.Failure
+    // Requires a package-qualified class name so ordinary prose is not taken for a header
+    static ref JAVA_HEADER: Regex = Regex::new(
+        r#"^\s*(?:Exception in thread "[^"]*"\s+)?(?:(Caused by|Suppressed):\s*)?((?:[a-zA-Z_$][\w$]*\.)+[A-Z][\w$]*)(?::\s?(.*))?$"#
+    ).unwrap();
+
+    // at com.acme.Service.run(Service.java:42) / at java.base/java.lang.Thread.run(Unknown Source)
+    static ref JAVA_FRAME: Regex = Regex::new(
+        r"^\s*at\s+(?:[\w.$-]+(?:@[\w.-]+)?/)?([\w$.<>]+)\(([^):]*)(?::(\d+))?\)"
+    ).unwrap();
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a JVM stack trace, including its `Caused by:` / `Suppressed:` chain
+/// Returns None unless the record holds an exception header followed by frames or causes
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let start = lines.iter().position(|l| {
+        JAVA_HEADER.captures(l).is_some_and(|caps| caps.get(1).is_none())
+    })?;
+
+    let mut chain: Vec<(CauseKind, DecodedException)> = Vec::new();
+    let mut current: Option<(CauseKind, DecodedException)> = None;
+    let mut in_suppressed = false;
+
+    for line in &lines[start..] {
+        if let Some(frame) = parse_frame(line) {
+            if let Some((_, exception)) = current.as_mut() {
+                exception.frames.push(frame);
+            }
+            continue;
+        }
+
+        let Some(caps) = JAVA_HEADER.captures(line) else {
+            continue;
+        };
+
+        // Only the first unprefixed header starts the trace; later ones belong to another record
+        let kind = match caps.get(1).map(|m| m.as_str()) {
+            Some("Suppressed") => {
+                in_suppressed = true;
+                CauseKind::Suppressed
+            }
+            // Causes printed inside a suppressed block describe the suppressed exception
+            Some(_) if in_suppressed && line.starts_with(char::is_whitespace) => CauseKind::Suppressed,
+            Some(_) => {
+                in_suppressed = false;
+                CauseKind::Cause
+            }
+            None if current.is_some() => break,
+            None => CauseKind::Cause,
+        };
+
+        chain.extend(current.take());
+        current = Some((kind, DecodedException {
+            exception_type: Some(caps[2].to_string()),
+            message: caps.get(3).map(|m| m.as_str().trim().to_string()).unwrap_or_default(),
+            ..DecodedException::default()
+        }));
+    }
+    chain.extend(current);
+
+    let mut links = chain.into_iter();
+    let (_, mut exception) = links.next()?;
+    exception.causes = links.map(|(kind, cause)| cause.into_cause(kind)).collect();
+
+    if exception.frames.is_empty() && exception.causes.is_empty() {
+        return None;
+    }
+    Some(exception)
+}
+
+/// Parse an `at com.acme.Service.run(Service.java:42)` frame line
+/// The declaring class becomes the module
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    let caps = JAVA_FRAME.captures(line)?;
+    let (module, function) = split_qualified(&caps[1], ".");
+    Some(StackFrame {
+        function,
+        module,
+        // "Native Method" / "Unknown Source" carry no file
+        file: Some(caps[2].to_string()).filter(|f| f.contains('.')),
+        line: caps.get(3).and_then(|m| m.as_str().parse().ok()),
+        column: None,
+        language: Some(Language::Java),
+        raw: line.trim().to_string(),
+        ..StackFrame::default()
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_decode_cause_chain() {
+        let exception = decode(&lines("org.springframework.web.util.NestedServletException: Request processing failed
+\tat org.springframework.web.servlet.FrameworkServlet.processRequest(FrameworkServlet.java:1014)
+\tat java.base/java.lang.Thread.run(Thread.java:833)
+Caused by: com.acme.billing.PaymentException: card 4111 declined
+\tat com.acme.billing.Gateway.charge(Gateway.java:88)
+\t... 12 more
+\tSuppressed: java.io.IOException: close failed
+\t\tat com.acme.io.Stream.close(Stream.java:10)
+Caused by: java.net.SocketTimeoutException: Read timed out
+\tat java.base/java.net.SocketInputStream.socketRead0(Native Method)
+\t... 20 more")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("org.springframework.web.util.NestedServletException"));
+        assert_eq!(exception.message, "Request processing failed");
+        assert_eq!(exception.frames.len(), 2);
+        assert_eq!(exception.frames[0].file.as_deref(), Some("FrameworkServlet.java"));
+        assert_eq!(exception.frames[0].line, Some(1014));
+        assert_eq!(exception.frames[0].module.as_deref(), Some("org.springframework.web.servlet.FrameworkServlet"));
+        assert_eq!(exception.frames[0].function.as_deref(), Some("processRequest"));
+
+        let kinds: Vec<CauseKind> = exception.causes.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![CauseKind::Cause, CauseKind::Suppressed, CauseKind::Cause]);
+        assert_eq!(exception.causes[0].file.as_deref(), Some("Gateway.java"));
+        assert_eq!(exception.causes[2].exception_type.as_deref(), Some("java.net.SocketTimeoutException"));
+        assert_eq!(exception.causes[2].frames[0].file, None);
+    }
+
+    #[test]
+    fn test_uncaught_thread_header() {
+        let exception = decode(&lines("Exception in thread \"main\" java.lang.NullPointerException: user is null
+\tat com.acme.App.main(App.java:7)")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("java.lang.NullPointerException"));
+        assert_eq!(exception.message, "user is null");
+    }
+
+    #[test]
+    fn test_plain_lines_are_not_java() {
+        assert!(decode(&lines("Request failed: see logs")).is_none());
+        assert!(decode(&lines("com.acme.Config: loaded")).is_none());
+    }
+}
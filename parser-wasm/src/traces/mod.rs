@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+// Language-specific stack trace decoders
+pub mod dotnet;
+pub mod go;
+pub mod in_app;
+pub mod java;
+pub mod native;
+pub mod node;
+pub mod php;
+pub mod proguard;
+pub mod python;
+pub mod ruby;
+pub mod rust;
+pub mod sourcemap;
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Language a frame was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Python,
+    Java,
+    Javascript,
+    Dotnet,
+    Go,
+    Rust,
+    Ruby,
+    Php,
+    /// C / C++ frames from sanitizers, gdb and libunwind
+    Native,
+}
+
+/// A single stack frame
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StackFrame {
+    /// Function or method name, without its module
+    pub function: Option<String>,
+    /// Java class, .NET type, Go package, Rust path, PHP class or native shared object
+    pub module: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub language: Option<Language>,
+    /// The frame as printed in the log
+    pub raw: String,
+    /// Application code rather than a dependency, runtime or standard library (see `in_app::classify`)
+    pub in_app: bool,
+}
+
+/// How a cause relates to the exception that carries it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CauseKind {
+    /// Java `Caused by:`, Python `raise ... from`, Node `Error.cause`, .NET inner exceptions
+    Cause,
+    /// Python implicit chaining ("During handling of the above exception ...")
+    Context,
+    /// Java `Suppressed:` (try-with-resources); never part of the root-cause chain
+    Suppressed,
+}
+
+/// One link of an exception chain, ordered from the outer exception down to the root cause
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorCause {
+    pub kind: CauseKind,
+    pub exception_type: Option<String>,
+    pub message: String,
+    pub frames: Vec<StackFrame>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// An exception decoded from a stack trace
+/// Frames are ordered innermost (most recent call) first, whatever order the language prints them
+#[derive(Debug, Clone, Default)]
+pub struct DecodedException {
+    pub exception_type: Option<String>,
+    pub message: String,
+    pub frames: Vec<StackFrame>,
+    pub causes: Vec<ErrorCause>,
+    /// Index of the frame the error is attributed to (skips runtime frames where the decoder can tell)
+    pub culprit: usize,
+    /// The process crashed (Go and Rust panics, PHP fatal errors, sanitizer reports)
+    pub fatal: bool,
+    /// Grouping key built from normalised frames, used instead of the location when set
+    pub frame_signature: Option<String>,
+    /// Decoder-specific details, merged into the error's attributes
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl DecodedException {
+    /// Location of the culprit frame (the innermost frame unless the decoder chose another)
+    pub fn location(&self) -> (Option<String>, Option<u32>, Option<u32>) {
+        match self.frames.get(self.culprit).or(self.frames.first()) {
+            Some(frame) => (frame.file.clone(), frame.line, frame.column),
+            None => (None, None, None),
+        }
+    }
+
+    /// Convert into a cause entry of another exception
+    /// Its own causes are flattened by the caller, so they are dropped here
+    pub fn into_cause(self, kind: CauseKind) -> ErrorCause {
+        let (file, line, column) = self.location();
+        ErrorCause {
+            kind,
+            exception_type: self.exception_type,
+            message: self.message,
+            frames: self.frames,
+            file,
+            line,
+            column,
+        }
+    }
+}
+
+/// Parse stack frames line by line, for stacks no decoder claimed as a whole
+/// (a stack under a plain ERROR line, or a stack field of a structured record)
+pub fn parse_frames<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<StackFrame> {
+    lines.into_iter().filter_map(parse_frame).collect()
+}
+
+/// Parse one frame line in any language whose frames fit on a single line
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    // .NET first: the JVM frame pattern also matches "at Foo.Bar()"
+    dotnet::parse_frame(line)
+        .or_else(|| java::parse_frame(line))
+        .or_else(|| node::parse_frame(line))
+        .or_else(|| python::parse_frame(line))
+        .or_else(|| ruby::parse_frame(line))
+        .or_else(|| php::parse_frame(line))
+        .or_else(|| native::parse_frame(line))
+}
+
+/// Split a qualified name at its last separator: "com.acme.Service.run" -> ("com.acme.Service", "run")
+pub fn split_qualified(name: &str, separator: &str) -> (Option<String>, Option<String>) {
+    match name.rsplit_once(separator) {
+        Some((module, function)) if !module.is_empty() && !function.is_empty() => {
+            (Some(module.to_string()), Some(function.to_string()))
+        }
+        _ => (None, Some(name.to_string())),
+    }
+}
+
+/// Deepest non-suppressed cause of a chain
+pub fn root_cause(causes: &[ErrorCause]) -> Option<&ErrorCause> {
+    causes.iter().rev().find(|c| c.kind != CauseKind::Suppressed)
+}
+
+/// Test fixture: a multi-line string as the record lines the decoders take
+#[cfg(test)]
+pub(crate) fn lines(text: &str) -> Vec<String> {
+    text.lines().map(|l| l.to_string()).collect()
+}
@@ -0,0 +1,299 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // ==1234==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc ...
+    // ==1234==WARNING: ThreadSanitizer: data race (pid=1234)
+    static ref SANITIZER_HEADER: Regex = Regex::new(
+        r"^==\d+==\s*(?:ERROR|WARNING): (\w+Sanitizer): (.+?)(?: on (?:unknown )?address .*| \(pid=\d+\).*)?$"
+    ).unwrap();
+
+    // src/dec.c:211:9: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'
+    static ref UBSAN_HEADER: Regex = Regex::new(
+        r"^(\S+?):(\d+):(\d+): runtime error: (.+)$"
+    ).unwrap();
+
+    // Program received signal SIGSEGV, Segmentation fault. / Thread 2 "worker" received signal SIGABRT, Aborted.
+    static ref GDB_SIGNAL: Regex = Regex::new(
+        r#"^(?:Program received signal|Program terminated with signal|Thread \d+ "[^"]*" received signal) (SIG\w+), (.+?)\.?$"#
+    ).unwrap();
+
+    // #0 0x55d4c2a1b2c3 in parse_frame /src/dec.c:211:9 / #2 0x7f3c2a1b in __libc_start_main (/lib/libc.so.6+0x21b96)
+    static ref SANITIZER_FRAME: Regex = Regex::new(
+        r"^\s*#(\d+)\s+0x[0-9a-f]+(?:\s+in\s+(.+?))?(?:\s+\(([^)]+?)(?:\+0x[0-9a-f]+)?\)|\s+(\S+?):(\d+)(?::(\d+))?)?\s*$"
+    ).unwrap();
+
+    // #0  parse_frame (buf=0x0, len=12) at src/dec.c:211 / #1  0x00007f3c in raise () from /lib/libc.so.6
+    static ref GDB_FRAME: Regex = Regex::new(
+        r"^#(\d+)\s+(?:0x[0-9a-f]+ in )?(\S+) \(.*?\)(?: at (\S+):(\d+)| from (\S+))?\s*$"
+    ).unwrap();
+
+    // ==1234==ABORTING / SUMMARY: AddressSanitizer: ... / READ of size 4 at ... / freed by thread T0 here:
+    static ref REPORT_LINE: Regex = Regex::new(
+        r"^(?:=+$|==\d+==|SUMMARY: |(?:READ|WRITE) of size |0x[0-9a-f]+ is located |(?:freed|previously allocated|allocated) by thread |Thread T\d+ |Shadow byte|Address 0x|HINT: |The signal is caused by |(?:Direct|Indirect) leak of |AddressSanitizer |Location is |Previous (?:read|write) |Mutex |\(gdb\)|0x[0-9a-f]+ in |\d+\t|#\d+\s)"
+    ).unwrap();
+}
+
+/// Runtime, libc and sanitizer interceptor frames that sit above the code at fault
+const RUNTIME_FUNCTIONS: [&str; 14] = [
+    "__interceptor_", "__asan_", "__sanitizer", "__ubsan", "__tsan", "__msan", "__lsan",
+    "malloc", "free", "operator new", "operator delete", "__GI_", "raise", "abort",
+];
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether `line` belongs to the sanitizer report or gdb backtrace being framed in `pending`
+/// Reports mix unindented lines, indented frames and blank lines; they end at `==pid==ABORTING`
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    let Some(first) = pending.first() else {
+        return false;
+    };
+    let is_report = first.starts_with("====") ||
+                    SANITIZER_HEADER.is_match(first) ||
+                    UBSAN_HEADER.is_match(first) ||
+                    GDB_SIGNAL.is_match(first);
+    if !is_report || pending.last().is_some_and(|l| l.ends_with("==ABORTING")) {
+        return false;
+    }
+
+    line.trim().is_empty() || line.starts_with(char::is_whitespace) || REPORT_LINE.is_match(line)
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode an ASan/TSan/MSan/LSan report, a UBSan runtime error or a gdb backtrace after a signal
+/// The sanitizer kind becomes the message; grouping uses the normalised frames, never addresses
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let (start, mut exception) = lines.iter().enumerate().find_map(|(i, line)| {
+        decode_header(line).map(|exception| (i, exception))
+    })?;
+
+    // Only the first stack is the crash; later ones say where memory was freed or allocated
+    let stack = parse_first_stack(&lines[start + 1..]);
+    if stack.is_empty() && exception.frames.is_empty() {
+        return None;
+    }
+    if !stack.is_empty() {
+        exception.frames = stack;
+    }
+
+    exception.culprit = exception.frames.iter().position(is_app_frame).unwrap_or(0);
+    exception.frame_signature = Some(frame_signature(&exception.frames[exception.culprit..]));
+    exception.fatal = true;
+    Some(exception)
+}
+
+fn decode_header(line: &str) -> Option<DecodedException> {
+    if let Some(caps) = SANITIZER_HEADER.captures(line) {
+        return Some(DecodedException {
+            exception_type: Some(caps[1].to_string()),
+            message: caps[2].trim().to_string(),
+            ..DecodedException::default()
+        });
+    }
+
+    if let Some(caps) = UBSAN_HEADER.captures(line) {
+        // Without print_stacktrace=1 the header location is the only frame
+        return Some(DecodedException {
+            exception_type: Some("UndefinedBehaviorSanitizer".to_string()),
+            message: caps[4].to_string(),
+            frames: vec![StackFrame {
+                file: Some(caps[1].to_string()),
+                line: caps[2].parse().ok(),
+                column: caps[3].parse().ok(),
+                language: Some(Language::Native),
+                raw: line.trim().to_string(),
+                ..StackFrame::default()
+            }],
+            ..DecodedException::default()
+        });
+    }
+
+    let caps = GDB_SIGNAL.captures(line)?;
+    Some(DecodedException {
+        exception_type: Some(caps[1].to_string()),
+        message: caps[2].to_string(),
+        ..DecodedException::default()
+    })
+}
+
+fn parse_first_stack(lines: &[String]) -> Vec<StackFrame> {
+    let mut frames = Vec::new();
+
+    for line in lines {
+        let Some((number, frame)) = parse_numbered_frame(line) else {
+            if !frames.is_empty() && line.trim().is_empty() {
+                break;
+            }
+            continue;
+        };
+        if number == 0 && !frames.is_empty() {
+            break;
+        }
+        frames.push(frame);
+    }
+
+    frames
+}
+
+/// Parse a single sanitizer or gdb frame line
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    parse_numbered_frame(line).map(|(_, frame)| frame)
+}
+
+/// Whether a line is a frame with an address, a location or a library, so that
+/// "#2 retry (later)" in an ordinary log isn't taken for a gdb frame
+pub fn is_frame_line(line: &str) -> bool {
+    parse_frame(line).is_some_and(|frame| {
+        frame.file.is_some() ||
+            frame.module.is_some() ||
+            line.split_whitespace().nth(1).is_some_and(|word| word.starts_with("0x"))
+    })
+}
+
+fn parse_numbered_frame(line: &str) -> Option<(u32, StackFrame)> {
+    if let Some(caps) = SANITIZER_FRAME.captures(line) {
+        let frame = StackFrame {
+            function: caps.get(2).map(|m| m.as_str().trim().to_string()),
+            module: caps.get(3).map(|m| m.as_str().to_string()),
+            file: caps.get(4).map(|m| m.as_str().to_string()),
+            line: caps.get(5).and_then(|m| m.as_str().parse().ok()),
+            column: caps.get(6).and_then(|m| m.as_str().parse().ok()),
+            language: Some(Language::Native),
+            raw: line.trim().to_string(),
+            ..StackFrame::default()
+        };
+        return Some((caps[1].parse().ok()?, frame));
+    }
+
+    let caps = GDB_FRAME.captures(line.trim_start())?;
+    let frame = StackFrame {
+        function: Some(caps[2].to_string()),
+        module: caps.get(5).map(|m| m.as_str().to_string()),
+        file: caps.get(3).map(|m| m.as_str().to_string()),
+        line: caps.get(4).and_then(|m| m.as_str().parse().ok()),
+        column: None,
+        language: Some(Language::Native),
+        raw: line.trim().to_string(),
+        ..StackFrame::default()
+    };
+    Some((caps[1].parse().ok()?, frame))
+}
+
+/// Frames with source info outside the sanitizer runtime, libc and system headers
+pub fn is_app_frame(frame: &StackFrame) -> bool {
+    let Some(file) = frame.file.as_deref() else {
+        return false;
+    };
+    let function = frame.function.as_deref().unwrap_or_default();
+
+    !RUNTIME_FUNCTIONS.iter().any(|prefix| function.starts_with(prefix)) &&
+        !file.starts_with("/usr/") &&
+        !file.contains("compiler-rt") &&
+        !file.contains("libsanitizer")
+}
+
+/// "parse_frame | decode_packet | main" - function names from the culprit down
+/// Addresses, offsets and line numbers change between builds, so they are left out
+fn frame_signature(frames: &[StackFrame]) -> String {
+    frames.iter()
+        .take(5)
+        .map(|frame| {
+            // Modules stand in for the function when there are no symbols
+            let function = frame.function.as_deref().or(frame.module.as_deref()).unwrap_or("?");
+            // "Foo::bar(int) const" -> "Foo::bar"
+            function.split_once('(').map_or(function, |(name, _)| name).trim()
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    const ASAN_REPORT: &str = "=================================================================
+==1234==ERROR: AddressSanitizer: heap-use-after-free on address 0x602000000010 at pc 0x55d4c2a1b2c3 bp 0x7ffd4 sp 0x7ffd8
+READ of size 4 at 0x602000000010 thread T0
+    #0 0x55d4c2a1b2c3 in parse_frame /src/dec.c:211:9
+    #1 0x55d4c2a1b400 in main /src/main.c:30:5
+    #2 0x7f3c2a1b2c3d in __libc_start_main (/lib/x86_64-linux-gnu/libc.so.6+0x21b96)
+
+0x602000000010 is located 0 bytes inside of 4-byte region [0x602000000010,0x602000000014)
+freed by thread T0 here:
+    #0 0x7f3c2a4e07b0 in free (/usr/lib/x86_64-linux-gnu/libasan.so.5+0xde7b0)
+    #1 0x55d4c2a1b100 in release_frame /src/dec.c:150:3
+
+SUMMARY: AddressSanitizer: heap-use-after-free /src/dec.c:211:9 in parse_frame
+Shadow bytes around the buggy address:
+  0x0c047fff7fb0: 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+==1234==ABORTING";
+
+    #[test]
+    fn test_decode_asan_report() {
+        let report = lines(ASAN_REPORT);
+        for i in 1..report.len() {
+            assert!(continues_record(&report[i], &report[..i]), "line {} split the report", i);
+        }
+        assert!(!continues_record("2026-10-16 23:05:01 INFO restarted", &report));
+
+        let exception = decode(&report).unwrap();
+        assert_eq!(exception.exception_type.as_deref(), Some("AddressSanitizer"));
+        assert_eq!(exception.message, "heap-use-after-free");
+        assert_eq!(exception.frames.len(), 3);
+        assert_eq!(exception.frames[2].function.as_deref(), Some("__libc_start_main"));
+        assert_eq!(exception.location(), (Some("/src/dec.c".to_string()), Some(211), Some(9)));
+        assert_eq!(exception.frame_signature.as_deref(), Some("parse_frame | main | __libc_start_main"));
+    }
+
+    #[test]
+    fn test_frame_lines_need_an_address_or_location() {
+        assert!(is_frame_line("    #3 0x55d4c2a1 in parse_frame src/dec.c:211:9"));
+        assert!(is_frame_line("#0  main () at src/main.c:30"));
+        assert!(is_frame_line("#1  0x00007f2a in __libc_start_main () from /lib/libc.so.6"));
+        assert!(!is_frame_line("#2 retry (later)"));
+    }
+
+    #[test]
+    fn test_signature_ignores_addresses() {
+        let other_run = ASAN_REPORT.replace("0x55d4c2a1", "0x5600aa00").replace("==1234==", "==987==");
+        assert_eq!(
+            decode(&lines(ASAN_REPORT)).unwrap().frame_signature,
+            decode(&lines(&other_run)).unwrap().frame_signature
+        );
+    }
+
+    #[test]
+    fn test_decode_ubsan_without_stack() {
+        let exception = decode(&lines("src/dec.c:211:9: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'")).unwrap();
+        assert_eq!(exception.message, "signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'");
+        assert_eq!(exception.location(), (Some("src/dec.c".to_string()), Some(211), Some(9)));
+    }
+
+    #[test]
+    fn test_decode_gdb_backtrace() {
+        let exception = decode(&lines("Program received signal SIGSEGV, Segmentation fault.
+0x000055d4c2a1b2c3 in parse_frame (buf=0x0, len=12) at src/dec.c:211
+211\t    return buf[0];
+(gdb) bt
+#0  0x000055d4c2a1b2c3 in parse_frame (buf=0x0, len=12) at src/dec.c:211
+#1  0x000055d4c2a1b400 in main (argc=1, argv=0x7ffd4) at src/main.c:30
+#2  0x00007f3c2a1b2c3d in __libc_start_main () from /lib/x86_64-linux-gnu/libc.so.6")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("SIGSEGV"));
+        assert_eq!(exception.message, "Segmentation fault");
+        assert_eq!(exception.frames.len(), 3);
+        assert_eq!(exception.location(), (Some("src/dec.c".to_string()), Some(211), None));
+    }
+}
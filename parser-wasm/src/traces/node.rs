@@ -0,0 +1,147 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{CauseKind, DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // TypeError: message / Error [ERR_HTTP_HEADERS_SENT]: message / [cause]: Error: message
+    static ref NODE_HEADER: Regex = Regex::new(
+        r"^\s*(\[cause\]:\s*)?([A-Z][\w$]*(?:Error|Exception)|Error)(?: \[[\w-]+\])?(?::\s?(.*))?$"
+    ).unwrap();
+
+    // at fn (/app/src/x.js:10:5) / at /app/src/x.js:10:5 / at async fn (file:///app/x.mjs:1:2)
+    static ref NODE_FRAME: Regex = Regex::new(
+        r"^\s*at\s+(?:(.+?)\s+\()?(.+):(\d+):(\d+)\)?(?:\s*\{)?$"
+    ).unwrap();
+}
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether a line belongs to the Node error in `pending` although it isn't a stack trace line:
+/// the brace closing the `{ ... }` block Node prints after the last frame for `[cause]` and
+/// other error properties. A bare "}" anywhere else (code, pretty-printed JSON) starts a record
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    if line.trim() != "}" {
+        return false;
+    }
+    let opened = pending.iter()
+        .filter(|l| l.trim_end().ends_with('{') && NODE_FRAME.is_match(l))
+        .count();
+    let closed = pending.iter().filter(|l| l.trim() == "}").count();
+    opened > closed
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a Node.js stack trace, following `[cause]:` blocks printed for `Error.cause`
+/// Returns None unless an error header is followed by `at` frames
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let start = lines.iter().position(|l| {
+        NODE_HEADER.captures(l).is_some_and(|caps| caps.get(1).is_none())
+    })?;
+
+    let mut chain: Vec<DecodedException> = Vec::new();
+    let mut current: Option<DecodedException> = None;
+
+    for line in &lines[start..] {
+        if let Some(frame) = parse_frame(line) {
+            if let Some(exception) = current.as_mut() {
+                exception.frames.push(frame);
+            }
+            continue;
+        }
+
+        let Some(caps) = NODE_HEADER.captures(line) else {
+            continue;
+        };
+        // A second top-level header is a different error
+        if caps.get(1).is_none() && current.is_some() {
+            break;
+        }
+
+        chain.extend(current.take());
+        current = Some(DecodedException {
+            exception_type: Some(caps[2].to_string()),
+            message: caps.get(3).map(|m| m.as_str().trim().to_string()).unwrap_or_default(),
+            ..DecodedException::default()
+        });
+    }
+    chain.extend(current);
+
+    let mut links = chain.into_iter();
+    let mut exception = links.next()?;
+    exception.causes = links.map(|cause| cause.into_cause(CauseKind::Cause)).collect();
+
+    if exception.frames.is_empty() {
+        return None;
+    }
+    Some(exception)
+}
+
+/// Parse an `at fn (/app/src/x.js:10:5)` frame line
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    let caps = NODE_FRAME.captures(line)?;
+    Some(StackFrame {
+        function: caps.get(1).map(|m| m.as_str().to_string()),
+        file: Some(caps[2].to_string()),
+        line: caps[3].parse().ok(),
+        column: caps[4].parse().ok(),
+        language: Some(Language::Javascript),
+        raw: line.trim().trim_end_matches('{').trim_end().to_string(),
+        ..StackFrame::default()
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_decode_error_cause() {
+        let exception = decode(&lines("Error: Failed to load user 42
+    at loadUser (/app/src/users.js:30:11)
+    at async handler (/app/src/routes.js:8:3) {
+  [cause]: Error: connect ECONNREFUSED 10.0.0.5:5432
+      at TCPConnectWrap.afterConnect [as oncomplete] (node:net:1555:16)
+}")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("Error"));
+        assert_eq!(exception.message, "Failed to load user 42");
+        assert_eq!(exception.frames.len(), 2);
+        assert_eq!(exception.frames[1].file.as_deref(), Some("/app/src/routes.js"));
+        assert_eq!(exception.frames[1].function.as_deref(), Some("async handler"));
+
+        assert_eq!(exception.causes.len(), 1);
+        let cause = &exception.causes[0];
+        assert_eq!(cause.message, "connect ECONNREFUSED 10.0.0.5:5432");
+        assert_eq!(cause.file.as_deref(), Some("node:net"));
+        assert_eq!(cause.line, Some(1555));
+    }
+
+    #[test]
+    fn test_only_the_brace_closing_a_frame_block_continues() {
+        let error = lines("Error: Failed to load user 42
+    at async handler (/app/src/routes.js:8:3) {
+  [cause]: Error: connect ECONNREFUSED 10.0.0.5:5432
+      at TCPConnectWrap.afterConnect [as oncomplete] (node:net:1555:16)");
+        assert!(continues_record("}", &error));
+
+        let closed = [error, lines("}")].concat();
+        assert!(!continues_record("}", &closed));
+        assert!(!continues_record("}", &lines("ERROR bad config: {\n  \"port\": 80")));
+    }
+
+    #[test]
+    fn test_header_without_frames_is_ignored() {
+        assert!(decode(&lines("TypeError: x is not a function")).is_none());
+    }
+}
@@ -0,0 +1,319 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{split_qualified, CauseKind, DecodedException, Language, StackFrame};
+use crate::formats::Level;
+
+lazy_static! {
+    // [16-Oct-2026 23:05:01 UTC] PHP Fatal error:  Uncaught Exception: boom in /var/www/app/x.php:12
+    static ref PHP_ERROR_LOG: Regex = Regex::new(
+        r"^(?:\[[^\]]+\] )?PHP ([A-Za-z ]+?):\s+(.*)$"
+    ).unwrap();
+
+    // [2026-10-16 23:05:01] production.ERROR: message {"exception":"[object] (...)"}
+    static ref LARAVEL_HEADER: Regex = Regex::new(
+        r"^\[\d{4}-\d{2}-\d{2}[ T][^\]]*\] [\w-]+\.([A-Z]+): (.*)$"
+    ).unwrap();
+
+    // Uncaught Exception: boom in /var/www/app/x.php:12 / Next RuntimeException: outer in /x.php:5
+    static ref PHP_EXCEPTION: Regex = Regex::new(
+        r"^(?:Uncaught |Next )([\w\\]+): (.*) in (\S+):(\d+)$"
+    ).unwrap();
+
+    // Allowed memory size of 134217728 bytes exhausted in /var/www/app/x.php on line 3
+    static ref ON_LINE: Regex = Regex::new(
+        r"^(.*) in (\S+) on line (\d+)$"
+    ).unwrap();
+
+    // [object] (Illuminate\Database\QueryException(code: 2002): SQLSTATE[HY000] refused at /var/www/x.php:70)
+    static ref LARAVEL_OBJECT: Regex = Regex::new(
+        r"\[object\] \(([\w\\]+)\(code: [^)]*\): (.*) at (\S+):(\d+)\)"
+    ).unwrap();
+
+    // #0 /var/www/app/y.php(88): Foo->bar() / #1 [internal function]: Foo->baz() / #2 {main}
+    static ref PHP_FRAME: Regex = Regex::new(
+        r"^#\d+ (?:(\S.*?)\((\d+)\)|\[internal function\]): (.+)$"
+    ).unwrap();
+
+    // "... in /var/www/app/x.php on line 12" / "... in /var/www/app/x.php:12" anywhere in a line
+    static ref PHP_LOCATION: Regex = Regex::new(
+        r" in (\S+?\.php)(?: on line |:)(\d+)\b"
+    ).unwrap();
+
+    // Any numbered trace line, including the closing "#3 {main}"
+    static ref FRAME_NUMBER: Regex = Regex::new(
+        r"^#\d+ "
+    ).unwrap();
+}
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether `line` belongs to the PHP error being framed in `pending`
+/// `Stack trace:`, `#N` frames and Laravel's `[stacktrace]` blocks are all unindented
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    let Some(first) = pending.first() else {
+        return false;
+    };
+    if !PHP_ERROR_LOG.is_match(first) && !LARAVEL_HEADER.is_match(first) {
+        return false;
+    }
+
+    let trimmed = line.trim();
+    trimmed == "Stack trace:" ||
+        trimmed == "[stacktrace]" ||
+        trimmed.starts_with("[previous exception] ") ||
+        trimmed.starts_with("thrown in ") ||
+        trimmed.starts_with("Next ") ||
+        trimmed.starts_with("\"}") ||
+        trimmed.is_empty() ||
+        FRAME_NUMBER.is_match(trimmed)
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Level named by a PHP error_log or Laravel line
+/// Fatal, parse and compile errors are fatal; notices and deprecations are informational
+pub fn level(line: &str) -> Option<Level> {
+    if let Some(caps) = PHP_ERROR_LOG.captures(line) {
+        let kind = caps[1].to_lowercase();
+        return Some(match kind.as_str() {
+            "warning" | "core warning" | "compile warning" | "user warning" => Level::Warn,
+            "notice" | "user notice" | "deprecated" | "user deprecated" | "strict standards" => Level::Info,
+            "recoverable fatal error" | "user error" => Level::Error,
+            _ if kind.ends_with("error") => Level::Fatal,
+            _ => Level::Error,
+        });
+    }
+
+    LARAVEL_HEADER.captures(line).and_then(|caps| Level::from_name(&caps[1]))
+}
+
+/// Decode a PHP error_log entry or a Laravel log entry at error level or above
+/// Earlier exceptions in the record (`Next` chains, Laravel `[previous exception]`) become causes
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let header = lines.first()?;
+    let level = level(header).filter(|level| *level >= Level::Error)?;
+
+    let mut exception = if let Some(caps) = PHP_ERROR_LOG.captures(header) {
+        decode_php(&caps[1], &caps[2], &lines[1..])
+    } else {
+        let caps = LARAVEL_HEADER.captures(header)?;
+        decode_laravel(&caps[2], lines)
+    };
+
+    exception.fatal = level == Level::Fatal;
+    Some(exception)
+}
+
+/// PHP prints chained exceptions innermost first, each following one introduced by "Next"
+fn decode_php(kind: &str, message: &str, rest: &[String]) -> DecodedException {
+    let mut chain = vec![decode_php_message(kind, message.trim())];
+
+    for line in rest {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Next ") {
+            chain.push(decode_php_message(kind, trimmed));
+        } else if let Some(frame) = parse_frame(trimmed) {
+            if let Some(exception) = chain.last_mut() {
+                exception.frames.push(frame);
+            }
+        }
+    }
+
+    let mut exception = chain.pop().unwrap_or_default();
+    exception.causes = chain.into_iter().rev().map(|cause| cause.into_cause(CauseKind::Cause)).collect();
+    exception
+}
+
+fn decode_php_message(kind: &str, message: &str) -> DecodedException {
+    // The throw location comes first; the "#N" frames list its callers
+    if let Some(caps) = PHP_EXCEPTION.captures(message) {
+        return DecodedException {
+            exception_type: Some(caps[1].to_string()),
+            message: caps[2].to_string(),
+            frames: vec![location_frame(&caps[0], &caps[3], &caps[4])],
+            ..DecodedException::default()
+        };
+    }
+
+    match ON_LINE.captures(message) {
+        Some(caps) => DecodedException {
+            exception_type: Some(kind.to_string()),
+            message: caps[1].to_string(),
+            frames: vec![location_frame(&caps[0], &caps[2], &caps[3])],
+            ..DecodedException::default()
+        },
+        None => DecodedException {
+            exception_type: Some(kind.to_string()),
+            message: message.to_string(),
+            ..DecodedException::default()
+        },
+    }
+}
+
+/// Laravel prints the thrown exception first, then each `[previous exception]` with its own `[stacktrace]`
+fn decode_laravel(message: &str, lines: &[String]) -> DecodedException {
+    let mut chain: Vec<DecodedException> = Vec::new();
+
+    for line in lines {
+        if let Some(caps) = LARAVEL_OBJECT.captures(line) {
+            chain.push(DecodedException {
+                exception_type: Some(unescape(&caps[1])),
+                message: unescape(&caps[2]),
+                frames: vec![location_frame(&caps[0], &caps[3], &caps[4])],
+                ..DecodedException::default()
+            });
+        } else if let Some(frame) = parse_frame(line.trim()) {
+            if let Some(exception) = chain.last_mut() {
+                exception.frames.push(frame);
+            }
+        }
+    }
+
+    let mut links = chain.into_iter();
+    let Some(mut exception) = links.next() else {
+        // Logged without an exception: the message minus its JSON context
+        let message = message.split(" {\"").next().unwrap_or(message);
+        return DecodedException {
+            message: message.trim_end_matches(" []").trim().to_string(),
+            ..DecodedException::default()
+        };
+    };
+    exception.causes = links.map(|cause| cause.into_cause(CauseKind::Cause)).collect();
+    exception
+}
+
+/// Parse a `#N file(line): Class->method(args)` trace line
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    let caps = PHP_FRAME.captures(line)?;
+
+    // "App\Repo->find(5)" -> ("App\Repo", "find"); static calls use "::"
+    let call = unescape(&caps[3]);
+    let call = call.split_once('(').map_or(call.as_str(), |(name, _)| name);
+    let (module, function) = if call.contains("->") {
+        split_qualified(call, "->")
+    } else {
+        split_qualified(call, "::")
+    };
+
+    Some(StackFrame {
+        function,
+        module,
+        file: caps.get(1).map(|m| m.as_str().to_string()),
+        line: caps.get(2).and_then(|m| m.as_str().parse().ok()),
+        column: None,
+        language: Some(Language::Php),
+        raw: line.trim().to_string(),
+        ..StackFrame::default()
+    })
+}
+
+/// Where a line points in PHP code: a `#N` trace line, or the "in file on line N" of an error message
+pub fn location(line: &str) -> Option<StackFrame> {
+    parse_frame(line.trim()).or_else(|| {
+        let caps = PHP_LOCATION.captures(line)?;
+        Some(location_frame(&caps[0], &caps[1], &caps[2]))
+    })
+}
+
+/// The "in file:line" of an error header, which is where it was thrown
+fn location_frame(raw: &str, file: &str, line: &str) -> StackFrame {
+    StackFrame {
+        file: Some(file.to_string()),
+        line: line.parse().ok(),
+        language: Some(Language::Php),
+        raw: raw.trim().to_string(),
+        ..StackFrame::default()
+    }
+}
+
+/// Laravel writes the exception inside a JSON string, so namespaces come out as `App\\Models`
+fn unescape(value: &str) -> String {
+    value.replace("\\\\", "\\")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(level("[16-Oct-2026 23:05:01 UTC] PHP Fatal error:  Out of memory"), Some(Level::Fatal));
+        assert_eq!(level("PHP Parse error:  syntax error, unexpected '}' in /x.php on line 3"), Some(Level::Fatal));
+        assert_eq!(level("PHP Warning:  Undefined variable $x in /x.php on line 5"), Some(Level::Warn));
+        assert_eq!(level("PHP Notice:  Undefined index: id in /x.php on line 9"), Some(Level::Info));
+        assert_eq!(level("PHP Deprecated:  Function strftime() is deprecated in /x.php on line 2"), Some(Level::Info));
+        assert_eq!(level("[2026-10-16 23:05:01] production.CRITICAL: down"), Some(Level::Fatal));
+        assert_eq!(level("2026-10-16 ERROR something"), None);
+    }
+
+    #[test]
+    fn test_decode_uncaught_chain() {
+        let exception = decode(&lines("[16-Oct-2026 23:05:01 UTC] PHP Fatal error:  Uncaught PDOException: Connection refused in /var/www/app/Db.php:14
+Stack trace:
+#0 /var/www/app/Db.php(14): PDO->__construct('mysql:host=db')
+#1 {main}
+
+Next App\\Exceptions\\StorageException: Storage unavailable in /var/www/app/Repo.php:30
+Stack trace:
+#0 /var/www/app/Controller.php(88): App\\Repo->find(5)
+#1 [internal function]: App\\Controller->show(5)
+#2 {main}
+  thrown in /var/www/app/Repo.php on line 30")).unwrap();
+
+        assert!(exception.fatal);
+        assert_eq!(exception.exception_type.as_deref(), Some("App\\Exceptions\\StorageException"));
+        assert_eq!(exception.message, "Storage unavailable");
+        assert_eq!(exception.location(), (Some("/var/www/app/Repo.php".to_string()), Some(30), None));
+        assert_eq!(exception.frames.len(), 3);
+        assert_eq!(exception.frames[1].module.as_deref(), Some("App\\Repo"));
+        assert_eq!(exception.frames[1].function.as_deref(), Some("find"));
+        assert_eq!(exception.frames[2].file, None);
+
+        assert_eq!(exception.causes.len(), 1);
+        assert_eq!(exception.causes[0].exception_type.as_deref(), Some("PDOException"));
+        assert_eq!(exception.causes[0].line, Some(14));
+    }
+
+    #[test]
+    fn test_decode_fatal_without_exception() {
+        let exception = decode(&lines("PHP Fatal error:  Allowed memory size of 134217728 bytes exhausted in /var/www/app/Export.php on line 41")).unwrap();
+        assert_eq!(exception.exception_type.as_deref(), Some("Fatal error"));
+        assert_eq!(exception.message, "Allowed memory size of 134217728 bytes exhausted");
+        assert_eq!(exception.location(), (Some("/var/www/app/Export.php".to_string()), Some(41), None));
+    }
+
+    #[test]
+    fn test_decode_laravel_entry() {
+        let entry = lines(r#"[2026-10-16 23:05:01] production.ERROR: SQLSTATE[HY000] [2002] Connection refused {"userId":1,"exception":"[object] (Illuminate\\Database\\QueryException(code: 2002): SQLSTATE[HY000] [2002] Connection refused at /var/www/vendor/laravel/framework/src/Illuminate/Database/Connection.php:760)
+[stacktrace]
+#0 /var/www/app/Http/Controllers/UserController.php(20): Illuminate\\Database\\Connection->select('select * from u...')
+#1 {main}
+
+[previous exception] [object] (PDOException(code: 2002): SQLSTATE[HY000] [2002] Connection refused at /var/www/vendor/laravel/framework/src/Illuminate/Database/Connectors/Connector.php:70)
+[stacktrace]
+#0 {main}
+"}"#);
+
+        for i in 1..entry.len() {
+            assert!(continues_record(&entry[i], &entry[..i]), "line {} split the entry", i);
+        }
+
+        let exception = decode(&entry).unwrap();
+        assert!(!exception.fatal);
+        assert_eq!(exception.exception_type.as_deref(), Some("Illuminate\\Database\\QueryException"));
+        assert_eq!(exception.message, "SQLSTATE[HY000] [2002] Connection refused");
+        assert_eq!(exception.frames[1].file.as_deref(), Some("/var/www/app/Http/Controllers/UserController.php"));
+        assert_eq!(exception.causes[0].exception_type.as_deref(), Some("PDOException"));
+        assert_eq!(exception.causes[0].line, Some(70));
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+lazy_static! {
+    // com.acme.orders.OrderService -> a.b.c:
+    static ref CLASS_LINE: Regex = Regex::new(
+        r"^(\S+) -> (\S+):$"
+    ).unwrap();
+
+    //     5:12:void load(java.lang.String):42:49 -> a / void close() -> b
+    // Inlined methods from another class are qualified: 13:13:int com.acme.Util.parse(java.lang.String):7:7 -> a
+    static ref METHOD_LINE: Regex = Regex::new(
+        r"^\s+(?:(\d+):(\d+):)?\S+ ([^\s(]+)\([^)]*\)(?::(\d+)(?::(\d+))?)? -> (\S+)$"
+    ).unwrap();
+
+    //     at a.b.c.a(SourceFile:12) / at a.b.c.a(Unknown Source)
+    static ref OBFUSCATED_FRAME: Regex = Regex::new(
+        r"^(\s*at\s+)([\w$.]+)\.([\w$<>]+)\([^):]*(?::(\d+))?\)(.*)$"
+    ).unwrap();
+
+    // a.b.d: Payment declined / Caused by: a.b.e / Exception in thread "main" a.b.d
+    static ref OBFUSCATED_HEADER: Regex = Regex::new(
+        r#"^(\s*(?:Exception in thread "[^"]*"\s+)?(?:(?:Caused by|Suppressed):\s*)?)([\w$]+(?:\.[\w$]+)+)(:.*)?$"#
+    ).unwrap();
+}
+
+/// R8 metadata comment following a class line: # {"id":"sourceFile","fileName":"OrderService.kt"}
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClassMetadata {
+    id: String,
+    file_name: Option<String>,
+}
+
+/// One `original -> obfuscated` method entry
+#[derive(Debug)]
+struct MethodMapping {
+    /// Obfuscated line range; None when the method has no line numbers
+    obfuscated_lines: Option<(u32, u32)>,
+    /// Set when the method was inlined from another class
+    class: Option<String>,
+    name: String,
+    original_start: Option<u32>,
+    original_end: Option<u32>,
+}
+
+#[derive(Debug)]
+struct ClassMapping {
+    original: String,
+    /// Methods by obfuscated name, in file order (inlined methods first, then their callers)
+    methods: HashMap<String, Vec<MethodMapping>>,
+}
+
+/// Translates obfuscated class and method names back using ProGuard / R8 `mapping.txt` files
+#[derive(Debug, Default)]
+pub struct ProguardMapping {
+    classes: HashMap<String, ClassMapping>,
+    /// Source file names from R8 metadata, by original class
+    source_files: HashMap<String, String>,
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+impl ProguardMapping {
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Add the classes of a mapping file; returns how many were read
+    pub fn add(&mut self, bytes: &[u8]) -> Result<usize, String> {
+        let text = String::from_utf8_lossy(bytes);
+        let mut current: Option<(String, ClassMapping)> = None;
+        let mut added = 0;
+
+        for line in text.lines() {
+            if let Some(comment) = line.trim_start().strip_prefix('#') {
+                let Some((_, class)) = &current else {
+                    continue;
+                };
+                if let Ok(ClassMetadata { id, file_name: Some(file) }) = serde_json::from_str(comment.trim()) {
+                    if id == "sourceFile" {
+                        self.source_files.insert(class.original.clone(), file);
+                    }
+                }
+            } else if let Some(caps) = CLASS_LINE.captures(line) {
+                if let Some((obfuscated, class)) = current.take() {
+                    self.classes.insert(obfuscated, class);
+                    added += 1;
+                }
+                current = Some((caps[2].to_string(), ClassMapping {
+                    original: caps[1].to_string(),
+                    methods: HashMap::new(),
+                }));
+            } else if let Some(caps) = METHOD_LINE.captures(line) {
+                let Some((_, class)) = current.as_mut() else {
+                    return Err(format!("method mapping before any class: {}", line.trim()));
+                };
+                let number = |i: usize| caps.get(i).and_then(|m| m.as_str().parse().ok());
+                let (class_name, name) = match caps[3].rsplit_once('.') {
+                    Some((class, name)) => (Some(class.to_string()), name.to_string()),
+                    None => (None, caps[3].to_string()),
+                };
+                class.methods.entry(caps[6].to_string()).or_default().push(MethodMapping {
+                    obfuscated_lines: number(1).zip(number(2)),
+                    class: class_name,
+                    name,
+                    original_start: number(4),
+                    original_end: number(5),
+                });
+            }
+            // Field mappings ("int count -> a") are not needed for stack traces
+        }
+
+        if let Some((obfuscated, class)) = current {
+            self.classes.insert(obfuscated, class);
+            added += 1;
+        }
+        if added == 0 {
+            return Err("no class mappings found".to_string());
+        }
+        Ok(added)
+    }
+}
+
+// ============================================================================
+// RETRACING
+// ============================================================================
+
+impl ProguardMapping {
+    /// Rewrite obfuscated frames and exception headers, like R8's `retrace`
+    /// A frame whose method had others inlined into it expands into one line per method
+    pub fn retrace(&self, lines: &[String]) -> Vec<String> {
+        let mut retraced = Vec::with_capacity(lines.len());
+        for line in lines {
+            match self.retrace_frame(line) {
+                Some(frames) => retraced.extend(frames),
+                None => retraced.push(self.retrace_header(line).unwrap_or_else(|| line.clone())),
+            }
+        }
+        retraced
+    }
+
+    /// Original name of an obfuscated class
+    pub fn class_name(&self, obfuscated: &str) -> Option<&str> {
+        self.classes.get(obfuscated).map(|class| class.original.as_str())
+    }
+
+    fn retrace_header(&self, line: &str) -> Option<String> {
+        let caps = OBFUSCATED_HEADER.captures(line)?;
+        let class = self.class_name(&caps[2])?;
+        Some(format!("{}{}{}", &caps[1], class, caps.get(3).map_or("", |m| m.as_str())))
+    }
+
+    fn retrace_frame(&self, line: &str) -> Option<Vec<String>> {
+        let caps = OBFUSCATED_FRAME.captures(line)?;
+        let class = self.classes.get(&caps[2])?;
+        let line_number: Option<u32> = caps.get(4).and_then(|m| m.as_str().parse().ok());
+        let (prefix, suffix) = (&caps[1], &caps[5]);
+
+        let frame = |class_name: &str, method: &str, line: Option<u32>| {
+            let file = self.source_file(class_name);
+            match line {
+                Some(line) => format!("{}{}.{}({}:{}){}", prefix, class_name, method, file, line, suffix),
+                None => format!("{}{}.{}({}){}", prefix, class_name, method, file, suffix),
+            }
+        };
+
+        let methods = class.methods.get(&caps[3]).map(Vec::as_slice).unwrap_or_default();
+        let candidates: Vec<&MethodMapping> = match line_number {
+            Some(line) => methods.iter()
+                .filter(|m| m.obfuscated_lines.is_some_and(|(start, end)| (start..=end).contains(&line)))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        if candidates.is_empty() {
+            // Without a line range the name is only known if every overload maps to the same method
+            let mut names = methods.iter().map(|m| (m.class.as_deref(), m.name.as_str()));
+            let method = match names.next() {
+                Some(first) if names.all(|other| other == first) => first.1,
+                _ => &caps[3],
+            };
+            return Some(vec![frame(&class.original, method, line_number)]);
+        }
+
+        Some(candidates.iter().map(|method| {
+            let class_name = method.class.as_deref().unwrap_or(&class.original);
+            frame(class_name, &method.name, original_line(method, line_number))
+        }).collect())
+    }
+
+    /// R8 records the file name; otherwise assume a Java file named after the top-level class
+    fn source_file(&self, class: &str) -> String {
+        if let Some(file) = self.source_files.get(class) {
+            return file.clone();
+        }
+        let simple = class.rsplit('.').next().unwrap_or(class);
+        format!("{}.java", simple.split('$').next().unwrap_or(simple))
+    }
+}
+
+/// Map an obfuscated line into the method's original range
+/// Inlined call sites carry a single original line, the line of the call
+fn original_line(method: &MethodMapping, line: Option<u32>) -> Option<u32> {
+    let (Some((start, end)), Some(line)) = (method.obfuscated_lines, line) else {
+        return line;
+    };
+    match (method.original_start, method.original_end) {
+        (Some(original_start), Some(original_end)) if original_end.checked_sub(original_start) == end.checked_sub(start) => {
+            Some(original_start + (line - start))
+        }
+        (Some(original_start), _) => Some(original_start),
+        (None, _) => Some(line),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    const MAPPING: &str = "# compiler: R8
+com.acme.orders.OrderService -> a.b.c:
+# {\"id\":\"sourceFile\",\"fileName\":\"OrderService.kt\"}
+    java.util.Map cache -> a
+    1:1:void <init>():12:12 -> <init>
+    5:8:com.acme.orders.Order load(long):40:43 -> a
+    9:9:int com.acme.orders.Prices.total(com.acme.orders.Order):17:17 -> a
+    9:9:com.acme.orders.Order load(long):44 -> a
+    void close() -> b
+com.acme.orders.PaymentException -> a.b.d:
+";
+
+    fn mapping() -> ProguardMapping {
+        let mut mapping = ProguardMapping::default();
+        assert_eq!(mapping.add(MAPPING.as_bytes()), Ok(2));
+        mapping
+    }
+
+    #[test]
+    fn test_retrace_frames_and_headers() {
+        let retraced = mapping().retrace(&lines("a.b.d: Payment declined
+\tat a.b.c.a(SourceFile:6)
+\tat a.b.c.b(Unknown Source)
+\tat java.lang.Thread.run(Thread.java:833)"));
+
+        assert_eq!(retraced, lines("com.acme.orders.PaymentException: Payment declined
+\tat com.acme.orders.OrderService.load(OrderService.kt:41)
+\tat com.acme.orders.OrderService.close(OrderService.kt)
+\tat java.lang.Thread.run(Thread.java:833)"));
+    }
+
+    #[test]
+    fn test_inlined_frames_expand() {
+        let retraced = mapping().retrace(&lines("\tat a.b.c.a(SourceFile:9)"));
+
+        assert_eq!(retraced, lines("\tat com.acme.orders.Prices.total(Prices.java:17)
+\tat com.acme.orders.OrderService.load(OrderService.kt:44)"));
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        assert!(ProguardMapping::default().add(b"{\"version\":3}").is_err());
+    }
+}
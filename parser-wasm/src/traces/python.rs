@@ -0,0 +1,268 @@
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{CauseKind, DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // File "/app/handlers.py", line 42, in handle
+    static ref PY_FRAME: Regex = Regex::new(
+        r#"^\s*File "([^"]+)", line (\d+)(?:, in (.+))?"#
+    ).unwrap();
+
+    // ValueError: bad value / requests.exceptions.ConnectionError: ... / KeyboardInterrupt
+    static ref PY_EXCEPTION: Regex = Regex::new(
+        r"^([A-Za-z_][\w.]*)(?::\s?(.*))?$"
+    ).unwrap();
+}
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+
+const CONTEXT_SEPARATOR: &str = "During handling of the above exception, another exception occurred:";
+const CAUSE_SEPARATOR: &str = "The above exception was the direct cause of the following exception:";
+
+/// Where a partially framed record stands with respect to Python tracebacks
+#[derive(Debug, PartialEq)]
+enum TracebackState {
+    /// No traceback in progress
+    None,
+    /// Inside a traceback, waiting for the exception line that ends it
+    Open,
+    /// The last traceback just ended with its exception line
+    Closed,
+    /// A chain separator was seen; another traceback is expected
+    Chained,
+}
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether `line` belongs to the Python traceback being framed in `pending`
+/// Python prints frames before the exception line, so the traceback stays open
+/// until the first unindented line after the frames, and chained tracebacks are
+/// kept in the same record
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    let trimmed = line.trim();
+
+    match traceback_state(pending) {
+        TracebackState::Open => true,
+        TracebackState::Closed => trimmed.is_empty() || is_chain_separator(trimmed),
+        TracebackState::Chained => trimmed.is_empty() || trimmed == TRACEBACK_HEADER,
+        // logging.exception() prints the log line first, then the traceback
+        TracebackState::None => {
+            trimmed == TRACEBACK_HEADER &&
+                last_non_blank(pending).is_some_and(crate::is_error_line)
+        }
+    }
+}
+
+fn traceback_state(pending: &[String]) -> TracebackState {
+    let mut lines = pending.iter().rev().map(String::as_str).filter(|l| !l.trim().is_empty());
+
+    let Some(last) = lines.next() else {
+        return TracebackState::None;
+    };
+    if last.trim() == TRACEBACK_HEADER {
+        return TracebackState::Open;
+    }
+    if is_indented(last) {
+        return if header_above(lines) { TracebackState::Open } else { TracebackState::None };
+    }
+    if is_chain_separator(last.trim()) {
+        return TracebackState::Chained;
+    }
+
+    // An unindented line right after the frames is the exception line
+    match lines.next() {
+        Some(previous) if is_indented(previous) && header_above(lines) => TracebackState::Closed,
+        _ => TracebackState::None,
+    }
+}
+
+/// Skip the remaining indented frame lines and check for a traceback header above them
+fn header_above<'a>(mut lines: impl Iterator<Item = &'a str>) -> bool {
+    lines.find(|l| !is_indented(l)).is_some_and(|l| l.trim() == TRACEBACK_HEADER)
+}
+
+fn last_non_blank(pending: &[String]) -> Option<&str> {
+    pending.iter().rev().map(String::as_str).find(|l| !l.trim().is_empty())
+}
+
+fn is_indented(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t')
+}
+
+fn is_chain_separator(trimmed: &str) -> bool {
+    trimmed == CONTEXT_SEPARATOR || trimmed == CAUSE_SEPARATOR
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode the tracebacks in a record into the exception that was finally raised
+/// Python prints chained exceptions root first, so earlier tracebacks become its
+/// causes (`raise ... from`) or context (raised while handling)
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let mut exceptions: Vec<(CauseKind, DecodedException)> = Vec::new();
+    let mut frames: Option<Vec<StackFrame>> = None;
+    let mut next_kind = CauseKind::Context;
+
+    for line in lines {
+        match line.trim() {
+            CAUSE_SEPARATOR => next_kind = CauseKind::Cause,
+            CONTEXT_SEPARATOR => next_kind = CauseKind::Context,
+            _ => {}
+        }
+
+        if line.trim() == TRACEBACK_HEADER {
+            frames = Some(Vec::new());
+            continue;
+        }
+
+        let Some(current) = frames.as_mut() else {
+            continue;
+        };
+
+        if let Some(frame) = parse_frame(line) {
+            current.push(frame);
+        } else if !line.trim().is_empty() && !is_indented(line) {
+            // Unindented line after the frames: the exception that ends the traceback
+            let mut frames = frames.take().unwrap_or_default();
+            frames.reverse();
+            exceptions.push((next_kind, decode_exception_line(line.trim(), frames)));
+        }
+    }
+
+    // The separator printed before a traceback says how the previous one relates to it
+    let (mut relation, mut raised) = exceptions.pop()?;
+    for (kind, exception) in exceptions.into_iter().rev() {
+        raised.causes.push(exception.into_cause(relation));
+        relation = kind;
+    }
+    Some(raised)
+}
+
+/// Parse a `File "...", line N, in func` frame line
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    let caps = PY_FRAME.captures(line)?;
+    Some(StackFrame {
+        function: caps.get(3).map(|m| m.as_str().trim().to_string()),
+        file: Some(caps[1].to_string()),
+        line: caps[2].parse().ok(),
+        language: Some(Language::Python),
+        raw: line.trim().to_string(),
+        ..StackFrame::default()
+    })
+}
+
+fn decode_exception_line(line: &str, frames: Vec<StackFrame>) -> DecodedException {
+    match PY_EXCEPTION.captures(line) {
+        Some(caps) => DecodedException {
+            exception_type: Some(caps[1].to_string()),
+            message: caps.get(2).map(|m| m.as_str().trim().to_string())
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| caps[1].to_string()),
+            frames,
+            ..DecodedException::default()
+        },
+        None => DecodedException {
+            message: line.to_string(),
+            frames,
+            ..DecodedException::default()
+        },
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    const CHAINED: &[&str] = &[
+        "ERROR:app:Request failed",
+        "Traceback (most recent call last):",
+        "  File \"/app/db.py\", line 10, in connect",
+        "    sock.connect(addr)",
+        "ConnectionRefusedError: [Errno 111] Connection refused",
+        "",
+        "During handling of the above exception, another exception occurred:",
+        "",
+        "Traceback (most recent call last):",
+        "  File \"/app/main.py\", line 5, in <module>",
+        "    run()",
+        "  File \"/app/service.py\", line 22, in run",
+        "    db.connect()",
+        "app.errors.DatabaseUnavailable: primary is down",
+    ];
+
+    fn frame(lines: &[&str]) -> Vec<Vec<String>> {
+        let mut records: Vec<Vec<String>> = Vec::new();
+        for line in lines {
+            match records.last_mut() {
+                Some(pending) if continues_record(line, pending) || crate::is_stack_trace_line(line) => {
+                    pending.push(line.to_string())
+                }
+                _ => records.push(vec![line.to_string()]),
+            }
+        }
+        records
+    }
+
+    #[test]
+    fn test_chained_traceback_is_one_record() {
+        let mut lines = CHAINED.to_vec();
+        lines.push("INFO:app:next request");
+        let records = frame(&lines);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].len(), CHAINED.len());
+    }
+
+    #[test]
+    fn test_decode_chained_traceback() {
+        let last = decode(&lines(&CHAINED.join("\n"))).unwrap();
+
+        assert_eq!(last.causes.len(), 1);
+        assert_eq!(last.causes[0].kind, CauseKind::Context);
+        assert_eq!(last.causes[0].exception_type.as_deref(), Some("ConnectionRefusedError"));
+        assert_eq!(last.causes[0].file.as_deref(), Some("/app/db.py"));
+
+        assert_eq!(last.exception_type.as_deref(), Some("app.errors.DatabaseUnavailable"));
+        assert_eq!(last.message, "primary is down");
+        // Innermost frame first
+        assert_eq!(last.frames[0].file.as_deref(), Some("/app/service.py"));
+        assert_eq!(last.frames[0].line, Some(22));
+        assert_eq!(last.frames[0].function.as_deref(), Some("run"));
+    }
+
+    #[test]
+    fn test_exception_without_message() {
+        let traceback = lines("Traceback (most recent call last):
+  File \"a.py\", line 1, in <module>
+KeyboardInterrupt");
+        assert_eq!(decode(&traceback).unwrap().message, "KeyboardInterrupt");
+    }
+
+    #[test]
+    fn test_explicit_cause_chain_order() {
+        let traceback = lines(&[
+            "Traceback (most recent call last):", "  File \"a.py\", line 1, in f", "KeyError: 'id'",
+            "", CAUSE_SEPARATOR, "",
+            "Traceback (most recent call last):", "  File \"b.py\", line 2, in g", "LookupFailed: no id",
+            "", CONTEXT_SEPARATOR, "",
+            "Traceback (most recent call last):", "  File \"c.py\", line 3, in h", "RuntimeError: giving up",
+        ].join("\n"));
+        let raised = decode(&traceback).unwrap();
+
+        assert_eq!(raised.message, "giving up");
+        let chain: Vec<(CauseKind, &str)> = raised.causes.iter()
+            .map(|c| (c.kind, c.message.as_str()))
+            .collect();
+        assert_eq!(chain, vec![(CauseKind::Context, "no id"), (CauseKind::Cause, "'id'")]);
+    }
+}
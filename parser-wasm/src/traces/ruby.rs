@@ -0,0 +1,304 @@
+use std::collections::VecDeque;
+
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{split_qualified, DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // Ruby Logger prefix and Rails log tags:
+    // F, [2026-10-16T23:05:01.123456 #1234] FATAL -- : [3f2c9a1e-...] message
+    static ref LOGGER_PREFIX: Regex = Regex::new(
+        r"^(?:[DIWEFA], \[[^\]]*\]\s+[A-Z]+ -- [^:]*: )?((?:\[[^\]]*\](?: |$))*)"
+    ).unwrap();
+
+    // ActiveRecord::RecordNotFound (Couldn't find User with 'id'=5):
+    static ref RAILS_HEADER: Regex = Regex::new(
+        r"^((?:[A-Z]\w*::)*[A-Z]\w*) \((.*)\):$"
+    ).unwrap();
+
+    // app/models/user.rb:42:in 'find_user': Couldn't find user (ActiveRecord::RecordNotFound)
+    static ref UNCAUGHT_HEADER: Regex = Regex::new(
+        r"^(\S+?):(\d+):in [`']([^']*)': (.*) \(((?:[A-Z]\w*::)*[A-Z]\w*)\)$"
+    ).unwrap();
+
+    // app/models/user.rb:42:in `find_user' / from bin/rails:4:in '<main>'
+    static ref RUBY_FRAME: Regex = Regex::new(
+        r"^\s*(?:from )?(\S+?):(\d+):in [`']([^']*)'$"
+    ).unwrap();
+
+    // Started GET "/users/5" for 127.0.0.1 at 2026-10-16 23:05:01 +0000
+    static ref REQUEST_STARTED: Regex = Regex::new(
+        r#"^Started ([A-Z]+) "([^"]*)""#
+    ).unwrap();
+
+    // Processing by UsersController#show as HTML
+    static ref REQUEST_PROCESSING: Regex = Regex::new(
+        r"^Processing by (\S+)"
+    ).unwrap();
+
+    // Completed 500 Internal Server Error in 12ms
+    static ref REQUEST_COMPLETED: Regex = Regex::new(
+        r"^Completed (\d{3})\b"
+    ).unwrap();
+}
+
+/// Requests kept open for linking; Puma interleaves a few per worker
+const MAX_OPEN_REQUESTS: usize = 64;
+
+/// A Rails request as logged by ActionController
+#[derive(Debug, Default)]
+struct RailsRequest {
+    /// Last log tag (usually the request id), None for untagged logs
+    tag: Option<String>,
+    method: String,
+    path: String,
+    controller: Option<String>,
+    status: Option<String>,
+}
+
+/// Follows `Started ... Completed` request logs so errors can be linked to their request
+/// Rails logs the exception after "Completed 500", so a request stays open until the
+/// next one with the same tag starts
+#[derive(Debug, Default)]
+pub struct RailsRequests {
+    open: VecDeque<RailsRequest>,
+}
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether `line` belongs to the Ruby backtrace being framed in `pending`
+/// Rails prints backtrace lines unindented after a blank line, so the generic
+/// stack trace rule would split them off
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    let Some(first) = pending.first() else {
+        return false;
+    };
+    let (_, first) = strip_prefix(first);
+    if !RAILS_HEADER.is_match(first) && !UNCAUGHT_HEADER.is_match(first) {
+        return false;
+    }
+
+    let (_, line) = strip_prefix(line);
+    line.trim().is_empty() || RUBY_FRAME.is_match(line)
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a Rails error block or an uncaught Ruby exception
+/// Returns None unless the record has a Ruby exception header
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let (start, tag, mut exception) = lines.iter().enumerate().find_map(|(i, line)| {
+        let (tag, line) = strip_prefix(line);
+        decode_header(line).map(|exception| (i, tag, exception))
+    })?;
+
+    for line in &lines[start + 1..] {
+        let (_, line) = strip_prefix(line);
+        if let Some(caps) = RUBY_FRAME.captures(line) {
+            exception.frames.push(frame_from_captures(line, &caps));
+        }
+    }
+
+    if let Some(tag) = tag {
+        exception.attributes.insert("request.id".to_string(), tag.to_string());
+    }
+    Some(exception)
+}
+
+/// Message of a Rails error header: "ActiveRecord::RecordNotFound (Couldn't find User with 'id'=5):"
+pub fn rails_message(line: &str) -> Option<&str> {
+    RAILS_HEADER.captures(line)
+        .and_then(|caps| caps.get(2))
+        .map(|m| m.as_str())
+        .filter(|message| !message.is_empty())
+}
+
+fn decode_header(line: &str) -> Option<DecodedException> {
+    if let Some(caps) = RAILS_HEADER.captures(line) {
+        return Some(DecodedException {
+            exception_type: Some(caps[1].to_string()),
+            message: caps[2].to_string(),
+            ..DecodedException::default()
+        });
+    }
+
+    // Uncaught exceptions print the innermost frame as part of the header
+    let caps = UNCAUGHT_HEADER.captures(line)?;
+    Some(DecodedException {
+        exception_type: Some(caps[5].to_string()),
+        message: caps[4].to_string(),
+        frames: vec![frame_from_captures(line, &caps)],
+        ..DecodedException::default()
+    })
+}
+
+/// Parse a single backtrace line
+pub fn parse_frame(line: &str) -> Option<StackFrame> {
+    let (_, line) = strip_prefix(line);
+    RUBY_FRAME.captures(line).map(|caps| frame_from_captures(line, &caps))
+}
+
+fn frame_from_captures(line: &str, caps: &regex::Captures) -> StackFrame {
+    // Ruby 3.4 labels methods with their owner: "Billing#charge" / "Billing.create"
+    // Blocks ("block in Billing#charge") and "<main>" are kept whole
+    let label = &caps[3];
+    let (module, function) = if label.contains(' ') {
+        (None, Some(label.to_string()))
+    } else if label.contains('#') {
+        split_qualified(label, "#")
+    } else {
+        split_qualified(label, ".")
+    };
+
+    StackFrame {
+        function,
+        module,
+        file: Some(caps[1].to_string()),
+        line: caps[2].parse().ok(),
+        column: None,
+        language: Some(Language::Ruby),
+        raw: line.trim().to_string(),
+        ..StackFrame::default()
+    }
+}
+
+/// Split off the Logger prefix; returns the last Rails log tag and the message
+fn strip_prefix(line: &str) -> (Option<&str>, &str) {
+    let Some(caps) = LOGGER_PREFIX.captures(line) else {
+        return (None, line);
+    };
+    let tags = caps.get(1).map_or("", |m| m.as_str());
+    let tag = tags.trim_end().rsplit("] ").next()
+        .map(|tag| tag.trim_start_matches('[').trim_end_matches(']'))
+        .filter(|tag| !tag.is_empty());
+    (tag, &line[caps.get(0).map_or(0, |m| m.end())..])
+}
+
+// ============================================================================
+// REQUEST LINKING
+// ============================================================================
+
+impl RailsRequests {
+    /// Track `Started` / `Processing by` / `Completed` lines
+    /// Returns whether the line was one of them
+    pub fn observe(&mut self, line: &str) -> bool {
+        let (tag, message) = strip_prefix(line);
+        let tag = tag.map(str::to_string);
+
+        if let Some(caps) = REQUEST_STARTED.captures(message) {
+            self.open.retain(|r| r.tag != tag);
+            if self.open.len() >= MAX_OPEN_REQUESTS {
+                self.open.pop_front();
+            }
+            self.open.push_back(RailsRequest {
+                tag,
+                method: caps[1].to_string(),
+                path: caps[2].to_string(),
+                ..RailsRequest::default()
+            });
+        } else if let Some(caps) = REQUEST_PROCESSING.captures(message) {
+            if let Some(request) = self.find(&tag) {
+                request.controller = Some(caps[1].to_string());
+            }
+        } else if let Some(caps) = REQUEST_COMPLETED.captures(message) {
+            if let Some(request) = self.find(&tag) {
+                request.status = Some(caps[1].to_string());
+            }
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Add the attributes of the request an exception was raised in
+    pub fn link(&self, mut exception: DecodedException) -> DecodedException {
+        let tag = exception.attributes.get("request.id").cloned();
+        let Some(request) = self.open.iter().rev().find(|r| r.tag == tag) else {
+            return exception;
+        };
+
+        let attributes = &mut exception.attributes;
+        attributes.insert("request.method".to_string(), request.method.clone());
+        attributes.insert("request.path".to_string(), request.path.clone());
+        if let Some(controller) = &request.controller {
+            attributes.insert("request.controller".to_string(), controller.clone());
+        }
+        if let Some(status) = &request.status {
+            attributes.insert("request.status".to_string(), status.clone());
+        }
+        exception
+    }
+
+    fn find(&mut self, tag: &Option<String>) -> Option<&mut RailsRequest> {
+        self.open.iter_mut().rev().find(|r| &r.tag == tag)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_decode_rails_error() {
+        let exception = decode(&lines("ActiveRecord::RecordNotFound (Couldn't find User with 'id'=5):
+
+app/models/user.rb:42:in `find_user'
+app/controllers/users_controller.rb:10:in `show'")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("ActiveRecord::RecordNotFound"));
+        assert_eq!(exception.message, "Couldn't find User with 'id'=5");
+        assert_eq!(exception.frames.len(), 2);
+        assert_eq!(exception.frames[0].function.as_deref(), Some("find_user"));
+        assert_eq!(exception.location(), (Some("app/models/user.rb".to_string()), Some(42), None));
+    }
+
+    #[test]
+    fn test_decode_uncaught_exception() {
+        let exception = decode(&lines("/app/lib/billing.rb:12:in 'Billing#charge': card declined (Billing::CardError)
+\tfrom /app/bin/worker:4:in '<main>'")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("Billing::CardError"));
+        assert_eq!(exception.message, "card declined");
+        assert_eq!(exception.frames[0].module.as_deref(), Some("Billing"));
+        assert_eq!(exception.frames[0].function.as_deref(), Some("charge"));
+        assert_eq!(exception.frames[1].file.as_deref(), Some("/app/bin/worker"));
+    }
+
+    #[test]
+    fn test_tagged_lines_are_one_record_and_linked() {
+        let log = lines("I, [2026-10-16T23:05:01.100 #7]  INFO -- : [req-1] Started GET \"/users/5\" for 10.0.0.1 at 2026-10-16 23:05:01 +0000
+I, [2026-10-16T23:05:01.101 #7]  INFO -- : [req-1] Processing by UsersController#show as HTML
+I, [2026-10-16T23:05:01.110 #7]  INFO -- : [req-1] Completed 500 Internal Server Error in 9ms
+F, [2026-10-16T23:05:01.111 #7] FATAL -- : [req-1] ActiveRecord::RecordNotFound (Couldn't find User with 'id'=5):
+F, [2026-10-16T23:05:01.111 #7] FATAL -- : [req-1]
+F, [2026-10-16T23:05:01.111 #7] FATAL -- : [req-1] app/models/user.rb:42:in `find_user'");
+
+        let mut requests = RailsRequests::default();
+        for line in &log[..3] {
+            requests.observe(line);
+        }
+        let record = &log[3..];
+        for i in 1..record.len() {
+            assert!(continues_record(&record[i], &record[..i]), "line {} split the record", i);
+        }
+
+        let exception = requests.link(decode(record).unwrap());
+        assert_eq!(exception.frames.len(), 1);
+        let attribute = |name: &str| exception.attributes.get(name).map(String::as_str);
+        assert_eq!(attribute("request.id"), Some("req-1"));
+        assert_eq!(attribute("request.method"), Some("GET"));
+        assert_eq!(attribute("request.path"), Some("/users/5"));
+        assert_eq!(attribute("request.controller"), Some("UsersController#show"));
+        assert_eq!(attribute("request.status"), Some("500"));
+    }
+}
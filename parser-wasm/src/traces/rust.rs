@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use super::{split_qualified, DecodedException, Language, StackFrame};
+
+lazy_static! {
+    // thread 'main' panicked at src/main.rs:10:5:              (Rust 1.73+, payload on the next lines)
+    // thread 'main' (12345) panicked at src/main.rs:10:5:      (with thread id)
+    // thread 'main' panicked at 'payload', src/main.rs:10:5    (older releases)
+    static ref PANIC_HEADER: Regex = Regex::new(
+        r"^thread '([^']*)'(?: \(\d+\))? panicked at (?:'(.*)', )?(\S+?):(\d+)(?::(\d+))?:?$"
+    ).unwrap();
+
+    //   12: myapp::config::load / 0:     0x55d4c2a1b2c3 - std::rt::lang_start::h0123456789abcdef
+    static ref BACKTRACE_FRAME: Regex = Regex::new(
+        r"^\s*\d+:\s+(?:0x[0-9a-f]+ - )?(.+?)\s*$"
+    ).unwrap();
+
+    //              at ./src/config.rs:44:9
+    static ref BACKTRACE_LOCATION: Regex = Regex::new(
+        r"^\s+at (.+?):(\d+)(?::(\d+))?\s*$"
+    ).unwrap();
+
+    // Symbol hash appended by RUST_BACKTRACE=full
+    static ref SYMBOL_HASH: Regex = Regex::new(
+        r"::h[0-9a-f]{16}$"
+    ).unwrap();
+}
+
+const BACKTRACE_HEADER: &str = "stack backtrace:";
+
+// ============================================================================
+// FRAMING
+// ============================================================================
+
+/// Whether `line` belongs to the panic being framed in `pending`
+/// Since Rust 1.73 the payload is printed unindented on the line after the header
+pub fn continues_record(line: &str, pending: &[String]) -> bool {
+    let Some(caps) = pending.first().and_then(|first| PANIC_HEADER.captures(first)) else {
+        return false;
+    };
+
+    let trimmed = line.trim();
+    (pending.len() == 1 && caps.get(2).is_none()) ||
+        line.starts_with(char::is_whitespace) ||
+        trimmed == BACKTRACE_HEADER ||
+        trimmed.starts_with("note: ")
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Decode a `thread '...' panicked at` message and its RUST_BACKTRACE frames
+/// The payload (`unwrap()` / `expect()` text, assertion details) becomes the message
+pub fn decode(lines: &[String]) -> Option<DecodedException> {
+    let start = lines.iter().position(|l| PANIC_HEADER.is_match(l))?;
+    let caps = PANIC_HEADER.captures(&lines[start])?;
+    let rest = &lines[start + 1..];
+
+    let message = match caps.get(2) {
+        Some(payload) => payload.as_str().to_string(),
+        None => rest.iter()
+            .map(|l| l.trim())
+            .take_while(|l| *l != BACKTRACE_HEADER && !l.starts_with("note: "))
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    let panic_frame = StackFrame {
+        file: Some(caps[3].to_string()),
+        line: caps[4].parse().ok(),
+        column: caps.get(5).and_then(|m| m.as_str().parse().ok()),
+        language: Some(Language::Rust),
+        raw: lines[start].trim().to_string(),
+        ..StackFrame::default()
+    };
+
+    // The backtrace starts inside the panic machinery; attribute the error to the panic
+    // location, which is the caller of unwrap()/expect() thanks to #[track_caller]
+    let mut frames = parse_backtrace(rest);
+    let culprit = match frames.iter().position(|f| same_location(f, &panic_frame)) {
+        Some(index) => index,
+        None => {
+            frames.insert(0, panic_frame);
+            0
+        }
+    };
+
+    let mut attributes = BTreeMap::new();
+    attributes.insert("thread".to_string(), caps[1].to_string());
+
+    Some(DecodedException {
+        exception_type: Some("panic".to_string()),
+        message,
+        frames,
+        culprit,
+        fatal: true,
+        attributes,
+        ..DecodedException::default()
+    })
+}
+
+/// Whether a line is a RUST_BACKTRACE frame ("  12: myapp::config::load"); Rust indents
+/// them, and unindented "1: ..." lines are too common in ordinary logs to count
+pub fn is_backtrace_frame(line: &str) -> bool {
+    line.starts_with(char::is_whitespace) && BACKTRACE_FRAME.is_match(line)
+}
+
+fn parse_backtrace(lines: &[String]) -> Vec<StackFrame> {
+    let mut frames: Vec<StackFrame> = Vec::new();
+
+    for line in lines.iter().skip_while(|l| l.trim() != BACKTRACE_HEADER) {
+        if let Some(caps) = BACKTRACE_LOCATION.captures(line) {
+            if let Some(frame) = frames.last_mut().filter(|f| f.file.is_none()) {
+                frame.file = Some(caps[1].to_string());
+                frame.line = caps[2].parse().ok();
+                frame.column = caps.get(3).and_then(|m| m.as_str().parse().ok());
+                frame.raw = format!("{}\n{}", frame.raw, line.trim());
+            }
+        } else if let Some(caps) = BACKTRACE_FRAME.captures(line) {
+            let (module, function) = split_qualified(&SYMBOL_HASH.replace(&caps[1], ""), "::");
+            frames.push(StackFrame {
+                function,
+                module,
+                language: Some(Language::Rust),
+                raw: line.trim().to_string(),
+                ..StackFrame::default()
+            });
+        }
+    }
+
+    frames
+}
+
+/// Backtraces print "./src/main.rs" where the panic header prints "src/main.rs"
+fn same_location(frame: &StackFrame, panic_frame: &StackFrame) -> bool {
+    let normalize = |file: &str| file.trim_start_matches("./").to_string();
+    frame.line == panic_frame.line &&
+        frame.file.as_deref().map(normalize) == panic_frame.file.as_deref().map(normalize)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::lines;
+
+    #[test]
+    fn test_decode_new_layout_with_backtrace() {
+        let exception = decode(&lines("thread 'tokio-runtime-worker' panicked at src/config.rs:44:9:
+called `Result::unwrap()` on an `Err` value: ParseIntError { kind: InvalidDigit }
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/90b35a6239c3d8bdabc530a6a0816f7ff89a0aaf/library/std/src/panicking.rs:645:5
+   1: core::result::unwrap_failed
+             at /rustc/90b35a6239c3d8bdabc530a6a0816f7ff89a0aaf/library/core/src/result.rs:1653:5
+   2: myapp::config::load
+             at ./src/config.rs:44:9
+   3: myapp::main
+             at ./src/main.rs:7:18
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.")).unwrap();
+
+        assert_eq!(exception.exception_type.as_deref(), Some("panic"));
+        assert_eq!(exception.message, "called `Result::unwrap()` on an `Err` value: ParseIntError { kind: InvalidDigit }");
+        assert_eq!(exception.frames.len(), 4);
+        assert_eq!(exception.frames[3].module.as_deref(), Some("myapp"));
+        assert_eq!(exception.frames[3].function.as_deref(), Some("main"));
+        assert_eq!(exception.location(), (Some("./src/config.rs".to_string()), Some(44), Some(9)));
+        assert_eq!(exception.attributes.get("thread").map(String::as_str), Some("tokio-runtime-worker"));
+        assert!(exception.fatal);
+    }
+
+    #[test]
+    fn test_decode_old_layout() {
+        let exception = decode(&lines("thread 'main' panicked at 'config missing: Os { code: 2 }', src/main.rs:4:37
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace")).unwrap();
+
+        assert_eq!(exception.message, "config missing: Os { code: 2 }");
+        assert_eq!(exception.location(), (Some("src/main.rs".to_string()), Some(4), Some(37)));
+    }
+
+    #[test]
+    fn test_full_backtrace_symbols() {
+        let frames = parse_backtrace(&lines("stack backtrace:
+   0:     0x55d4c2a1b2c3 - myapp::main::h0123456789abcdef
+                               at /app/src/main.rs:3:5"));
+
+        assert_eq!(frames[0].function.as_deref(), Some("main"));
+        assert_eq!(frames[0].raw, "0:     0x55d4c2a1b2c3 - myapp::main::h0123456789abcdef\nat /app/src/main.rs:3:5");
+        assert_eq!(frames[0].file.as_deref(), Some("/app/src/main.rs"));
+    }
+
+    #[test]
+    fn test_assertion_payload_is_one_record() {
+        let panic = lines("thread 'main' (7781) panicked at src/lib.rs:2:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace");
+
+        for i in 1..panic.len() {
+            assert!(continues_record(&panic[i], &panic[..i]), "line {} split the panic", i);
+        }
+        assert!(!continues_record("2026-10-16 23:05:01 INFO restarted", &panic));
+        assert_eq!(decode(&panic).unwrap().message, "assertion `left == right` failed left: 1 right: 2");
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::{Language, StackFrame};
+
+// ============================================================================
+// TYPES
+// ============================================================================
+
+/// Source map JSON (revision 3); index maps with "sections" are not supported
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    file: Option<String>,
+    #[serde(default)]
+    source_root: Option<String>,
+    sources: Vec<Option<String>>,
+    #[serde(default)]
+    names: Vec<String>,
+    mappings: String,
+}
+
+/// One mapping of a generated line, from `column` to the end of the line or the next segment
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    column: u32,
+    /// Source index, original line and column (all 0-based); None for unmapped generated code
+    original: Option<(u32, u32, u32)>,
+    name: Option<u32>,
+}
+
+/// A decoded source map
+#[derive(Debug)]
+pub struct SourceMap {
+    sources: Vec<String>,
+    names: Vec<String>,
+    /// Segments of each generated line, sorted by column
+    lines: Vec<Vec<Segment>>,
+}
+
+/// A generated position mapped back to the original source (1-based, like stack frames)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OriginalLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    /// Original identifier at that position, when the map records one
+    pub name: Option<String>,
+}
+
+/// Source maps keyed by the file name of the bundle they describe ("main.3f9a.js")
+#[derive(Debug, Default)]
+pub struct SourceMaps {
+    maps: HashMap<String, SourceMap>,
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+impl SourceMap {
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Option<String>, SourceMap), String> {
+        let raw: RawSourceMap = serde_json::from_slice(bytes).map_err(|e| format!("invalid source map: {}", e))?;
+
+        let root = raw.source_root.as_deref().unwrap_or_default().trim_end_matches('/');
+        let sources = raw.sources.into_iter()
+            .map(|source| {
+                let source = source.unwrap_or_default();
+                if root.is_empty() { source } else { format!("{}/{}", root, source) }
+            })
+            .collect();
+
+        let map = SourceMap {
+            sources,
+            names: raw.names,
+            lines: decode_mappings(&raw.mappings)?,
+        };
+        Ok((raw.file, map))
+    }
+
+    /// Original location of a 0-based generated line and column
+    /// Columns between two segments belong to the one on their left
+    pub fn lookup(&self, line: u32, column: u32) -> Option<OriginalLocation> {
+        let segments = self.lines.get(line as usize)?;
+        let index = segments.partition_point(|s| s.column <= column).checked_sub(1)?;
+        let segment = segments[index];
+        let (source, line, column) = segment.original?;
+
+        Some(OriginalLocation {
+            file: self.sources.get(source as usize)?.clone(),
+            line: line + 1,
+            column: column + 1,
+            name: segment.name.and_then(|name| self.names.get(name as usize)).cloned(),
+        })
+    }
+}
+
+/// Decode the Base64 VLQ "mappings" string
+/// Lines are separated by ';', segments by ','; every field but the generated
+/// column is relative to the previous segment in the whole map
+fn decode_mappings(mappings: &str) -> Result<Vec<Vec<Segment>>, String> {
+    let mut lines = Vec::new();
+    let (mut source, mut original_line, mut original_column, mut name) = (0i64, 0i64, 0i64, 0i64);
+
+    for line in mappings.split(';') {
+        let mut segments = Vec::new();
+        let mut column = 0i64;
+
+        for segment in line.split(',').filter(|s| !s.is_empty()) {
+            let fields = decode_vlq(segment)?;
+            column += fields[0];
+            let mut decoded = Segment { column: to_u32(column)?, original: None, name: None };
+
+            if fields.len() >= 4 {
+                source += fields[1];
+                original_line += fields[2];
+                original_column += fields[3];
+                decoded.original = Some((to_u32(source)?, to_u32(original_line)?, to_u32(original_column)?));
+            }
+            if fields.len() >= 5 {
+                name += fields[4];
+                decoded.name = Some(to_u32(name)?);
+            }
+            segments.push(decoded);
+        }
+
+        // Bundlers emit segments in order, but the spec doesn't require it
+        segments.sort_by_key(|s| s.column);
+        lines.push(segments);
+    }
+
+    Ok(lines)
+}
+
+fn decode_vlq(segment: &str) -> Result<Vec<i64>, String> {
+    let mut values = Vec::new();
+    let (mut value, mut shift) = (0i64, 0u32);
+
+    for c in segment.bytes() {
+        let digit = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(format!("invalid VLQ character {:?}", c as char)),
+        } as i64;
+        if shift > 60 {
+            return Err("VLQ value overflows".to_string());
+        }
+
+        value += (digit & 31) << shift;
+        if digit & 32 != 0 {
+            shift += 5;
+            continue;
+        }
+
+        // Lowest bit is the sign
+        values.push(if value & 1 == 1 { -(value >> 1) } else { value >> 1 });
+        (value, shift) = (0, 0);
+    }
+
+    if shift != 0 || values.is_empty() {
+        return Err(format!("truncated VLQ segment {:?}", segment));
+    }
+    Ok(values)
+}
+
+fn to_u32(value: i64) -> Result<u32, String> {
+    u32::try_from(value).map_err(|_| format!("mapping out of range: {}", value))
+}
+
+// ============================================================================
+// RESOLUTION
+// ============================================================================
+
+impl SourceMaps {
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+
+    /// Add a map; `name` is its file name ("main.3f9a.js.map"), used when the map has no "file" field
+    pub fn add(&mut self, name: &str, bytes: &[u8]) -> Result<(), String> {
+        let (file, map) = SourceMap::from_bytes(bytes)?;
+        let file = file.filter(|f| !f.is_empty())
+            .unwrap_or_else(|| name.strip_suffix(".map").unwrap_or(name).to_string());
+        self.maps.insert(bundle_name(&file).to_string(), map);
+        Ok(())
+    }
+
+    /// Add every `*.map` file in a directory (not recursive); returns how many were loaded
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_dir(&mut self, dir: &std::path::Path) -> std::io::Result<usize> {
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "map") {
+                continue;
+            }
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+            let bytes = std::fs::read(&path)?;
+            self.add(&name, &bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", name, e)))?;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Original location of a 1-based generated position in a bundle
+    pub fn lookup(&self, file: &str, line: u32, column: u32) -> Option<OriginalLocation> {
+        let map = self.maps.get(bundle_name(file))?;
+        map.lookup(line.checked_sub(1)?, column.saturating_sub(1))
+    }
+
+    /// Rewrite JavaScript frames to their original sources
+    /// A function's original name is the identifier its caller called it by, so each
+    /// frame takes its name from the position of the frame below it (frames are innermost first)
+    pub fn resolve(&self, frames: &mut [StackFrame]) {
+        let originals: Vec<Option<OriginalLocation>> = frames.iter()
+            .map(|frame| {
+                if frame.language != Some(Language::Javascript) {
+                    return None;
+                }
+                self.lookup(frame.file.as_deref()?, frame.line?, frame.column?)
+            })
+            .collect();
+
+        for (i, frame) in frames.iter_mut().enumerate() {
+            let Some(original) = &originals[i] else {
+                continue;
+            };
+            frame.file = Some(original.file.clone());
+            frame.line = Some(original.line);
+            frame.column = Some(original.column);
+            if let Some(name) = originals.get(i + 1).and_then(|caller| caller.as_ref()?.name.clone()) {
+                frame.function = Some(name);
+            }
+        }
+    }
+}
+
+/// "https://cdn.acme.com/static/js/main.3f9a.js?v=2" -> "main.3f9a.js"
+fn bundle_name(file: &str) -> &str {
+    let file = file.split(['?', '#']).next().unwrap_or(file);
+    file.rsplit(['/', '\\']).next().unwrap_or(file)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::parse_frames;
+
+    // Bundle: function a(){throw new Error("x")}function b(){a()}b();
+    // from src/users.ts, where a is loadUser (line 1) and b is main (line 5)
+    const MAP: &str = r#"{"version":3,"file":"main.3f9a.js","sourceRoot":"webpack://app/","sources":["src/users.ts"],"names":["loadUser","main"],"mappings":"SAASA,IACP,8BAGOC,IACPD"}"#;
+
+    #[test]
+    fn test_decode_vlq() {
+        assert_eq!(decode_vlq("AAgBC").unwrap(), vec![0, 0, 16, 1]);
+        assert_eq!(decode_vlq("D").unwrap(), vec![-1]);
+        assert!(decode_vlq("g").is_err());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut maps = SourceMaps::default();
+        maps.add("main.3f9a.js.map", MAP.as_bytes()).unwrap();
+
+        let original = maps.lookup("https://cdn.acme.com/js/main.3f9a.js?v=2", 1, 20).unwrap();
+        assert_eq!(original, OriginalLocation {
+            file: "webpack://app/src/users.ts".to_string(),
+            line: 2,
+            column: 3,
+            name: None,
+        });
+        assert!(maps.lookup("vendor.js", 1, 20).is_none());
+    }
+
+    #[test]
+    fn test_resolve_frames_and_names() {
+        let mut maps = SourceMaps::default();
+        maps.add("main.3f9a.js.map", MAP.as_bytes()).unwrap();
+
+        let mut frames = parse_frames([
+            "    at a (/srv/app/dist/main.3f9a.js:1:14)",
+            "    at b (/srv/app/dist/main.3f9a.js:1:48)",
+        ]);
+        maps.resolve(&mut frames);
+
+        assert_eq!(frames[0].file.as_deref(), Some("webpack://app/src/users.ts"));
+        assert_eq!((frames[0].line, frames[0].column), (Some(2), Some(3)));
+        assert_eq!(frames[0].function.as_deref(), Some("loadUser"));
+        assert_eq!(frames[1].line, Some(6));
+        // The outermost frame has no caller to name it
+        assert_eq!(frames[1].function.as_deref(), Some("b"));
+    }
+}
@@ -139,6 +139,12 @@ export function ErrorDetailSheet({ error, open, onOpenChange, onCopy }: ErrorDet
                                     <Clock className="h-4 w-4" aria-hidden="true" />
                                     <time dateTime={error.timestamp}>{error.timestamp}</time>
                                 </div>
+                                {error.first_seen && error.last_seen && error.first_seen !== error.last_seen && (
+                                    <p className="text-xs text-[#6b7280] dark:text-neutral-400 font-mono">
+                                        Seen from <time dateTime={error.first_seen}>{error.first_seen}</time>
+                                        {' '}to <time dateTime={error.last_seen}>{error.last_seen}</time>
+                                    </p>
+                                )}
                             </div>
                             <Separator className="bg-[#e5e7eb] dark:bg-white/10" />
                         </>
@@ -18,26 +18,30 @@ import {
 } from '@/components/ui/collapsible';
 import {
     type ParsedError,
+    type TimeHistogram,
     getSeverityColor,
     getTypeColor,
     formatOccurrences,
     formatLocation,
+    formatBucketSize,
 } from '@/lib/wasm-parser';
 import { PatternLearningModal } from './PatternLearningModal';
+import { Sparkline } from './Sparkline';
 import { clearAllPatterns, getPatterns } from '@/lib/pattern-storage';
 import { toast } from 'sonner';
 
 interface ErrorTableProps {
     errors: ParsedError[];
+    histogram?: TimeHistogram;
     onViewDetails: (error: ParsedError) => void;
     onReAnalyze: () => Promise<void>;
 }
 
 /**
  * Display errors in a sortable table with expandable rows
- * Shows error type, severity, message, occurrences, and expandable details
+ * Shows error type, severity, message, occurrences over time, and expandable details
  */
-export function ErrorTable({ errors, onViewDetails, onReAnalyze }: ErrorTableProps) {
+export function ErrorTable({ errors, histogram, onViewDetails, onReAnalyze }: ErrorTableProps) {
     const [expandedRows, setExpandedRows] = useState<Set<string>>(new Set());
     const [patternModalOpen, setPatternModalOpen] = useState(false);
     const [patternsCount, setPatternsCount] = useState(0);
@@ -169,6 +173,14 @@ export function ErrorTable({ errors, onViewDetails, onReAnalyze }: ErrorTablePro
                             <TableHead className="text-[#6b7280] dark:text-neutral-400 font-medium w-[100px]">Type</TableHead>
                             <TableHead className="text-[#6b7280] dark:text-neutral-400 font-medium w-[100px]">Severity</TableHead>
                             <TableHead className="text-[#6b7280] dark:text-neutral-400 font-medium">Message</TableHead>
+                            <TableHead className="text-[#6b7280] dark:text-neutral-400 font-medium w-[120px]">
+                                Trend
+                                {histogram && histogram.start && (
+                                    <span className="ml-1 text-[10px] font-mono text-[#9ca3af] dark:text-neutral-500">
+                                        /{formatBucketSize(histogram.bucket_seconds)}
+                                    </span>
+                                )}
+                            </TableHead>
                             <TableHead className="text-[#6b7280] dark:text-neutral-400 font-medium w-[120px] text-right">Occurrences</TableHead>
                             <TableHead className="text-[#6b7280] dark:text-neutral-400 font-medium w-[100px] text-right">Action</TableHead>
                         </TableRow>
@@ -225,6 +237,14 @@ export function ErrorTable({ errors, onViewDetails, onReAnalyze }: ErrorTablePro
                                             <TableCell className="text-[#111827] dark:text-white font-medium max-w-md truncate">
                                                 {error.template || error.message}
                                             </TableCell>
+                                            <TableCell>
+                                                {histogram?.by_fingerprint[error.fingerprint] && (
+                                                    <Sparkline
+                                                        counts={histogram.by_fingerprint[error.fingerprint]}
+                                                        label={`Occurrences of error #${error.index} over time`}
+                                                    />
+                                                )}
+                                            </TableCell>
                                             <TableCell className="text-right">
                                                 <div className="flex flex-col items-end gap-1">
                                                     <Badge variant="secondary" className="font-mono bg-[#f3f4f6] dark:bg-neutral-800 border-[#e5e7eb] dark:border-white/10 text-[#111827] dark:text-white">
@@ -255,7 +275,7 @@ export function ErrorTable({ errors, onViewDetails, onReAnalyze }: ErrorTablePro
                                         {/* Expanded Content */}
                                         <CollapsibleContent asChild>
                                             <TableRow className="border-[#e5e7eb] dark:border-white/5 hover:bg-transparent">
-                                                <TableCell colSpan={7} className="p-0">
+                                                <TableCell colSpan={8} className="p-0">
                                                     <div className="px-6 py-4 bg-[#f9fafb] dark:bg-white/[0.02] border-t border-[#e5e7eb] dark:border-white/5">
                                                         <div className="space-y-4">
                                                             {/* Metadata */}
@@ -46,6 +46,23 @@ export function ResultsView({
                 </Card>
             )}
 
+            {/* Compressed input that stopped early: the counts below miss the rest of the file */}
+            {result.decompression_error && (
+                <Card className="border-yellow-500/20 bg-yellow-500/5" role="alert" aria-live="polite">
+                    <CardContent className="pt-6">
+                        <div className="flex items-start gap-3">
+                            <AlertCircle className="h-5 w-5 text-yellow-400 shrink-0 mt-0.5" aria-hidden="true" />
+                            <div>
+                                <p className="text-yellow-400 font-medium">Incomplete Results</p>
+                                <p className="text-yellow-300/80 text-sm mt-1">
+                                    The compressed file could not be read to the end ({result.decompression_error}). Only the part before the error was analyzed.
+                                </p>
+                            </div>
+                        </div>
+                    </CardContent>
+                </Card>
+            )}
+
             {/* Summary Stats */}
             <SummaryCard
                 stats={result.summary}
@@ -63,6 +80,7 @@ export function ResultsView({
             {result.errors.length > 0 ? (
                 <ErrorTable
                     errors={result.errors}
+                    histogram={result.histogram}
                     onViewDetails={onViewDetails}
                     onReAnalyze={onReAnalyze}
                 />
@@ -0,0 +1,45 @@
+interface SparklineProps {
+    counts: number[];
+    label: string;
+    width?: number;
+    height?: number;
+}
+
+/**
+ * Tiny inline chart of occurrences per histogram bucket
+ * Bars share the height scale of the series, so the shape shows when an error happened
+ */
+export function Sparkline({ counts, label, width = 96, height = 24 }: SparklineProps) {
+    if (counts.length === 0) return null;
+
+    // reduce rather than spreading into Math.max, which overflows the call stack on long series
+    const max = counts.reduce((a, b) => Math.max(a, b), 1);
+    const barWidth = width / counts.length;
+
+    return (
+        <svg
+            width={width}
+            height={height}
+            viewBox={`0 0 ${width} ${height}`}
+            role="img"
+            aria-label={label}
+            className="text-[#1e40af] dark:text-blue-400"
+        >
+            {counts.map((count, i) => {
+                if (count === 0) return null;
+                // At least one pixel, so single occurrences stay visible next to large bursts
+                const barHeight = Math.max(1, (count / max) * height);
+                return (
+                    <rect
+                        key={i}
+                        x={i * barWidth}
+                        y={height - barHeight}
+                        width={Math.max(1, barWidth - 1)}
+                        height={barHeight}
+                        fill="currentColor"
+                    />
+                );
+            })}
+        </svg>
+    );
+}
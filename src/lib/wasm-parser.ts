@@ -33,10 +33,37 @@ export interface Variable {
     var_type: VariableType;
 }
 
+export type Language = 'python' | 'java' | 'javascript' | 'dotnet' | 'go' | 'rust' | 'ruby' | 'php' | 'native';
+
+export interface StackFrame {
+    function: string | null;  // Function or method name, without its module
+    module: string | null;    // Class, type, package, Rust path or shared object
+    file: string | null;
+    line: number | null;
+    column: number | null;
+    language: Language | null;
+    raw: string;              // The frame as printed in the log
+    in_app: boolean;          // Application code, not a dependency, runtime or standard library
+}
+
+export type CauseKind = 'cause' | 'context' | 'suppressed';
+
+// One link of an exception chain, from the outer exception down to the root cause
+export interface ErrorCause {
+    kind: CauseKind;
+    exception_type: string | null;
+    message: string;
+    frames: StackFrame[];   // Innermost frame first
+    file: string | null;
+    line: number | null;
+    column: number | null;
+}
+
 export interface ParsedError {
     id: string;
     type: ErrorType;
     severity: Severity;
+    exception_type: string | null; // Exception class when the log names one
     message: string;
     template: string;              // NEW: Normalized message with variable placeholders
     variables: Variable[];         // NEW: List of extracted variables
@@ -45,8 +72,17 @@ export interface ParsedError {
     line: number | null;
     column: number | null;
     occurrences: number;
-    timestamp: string | null;
+    timestamp: string | null;            // First occurrence, RFC 3339 in UTC ("2026-10-16T23:05:01.120Z")
+    first_seen: string | null;           // Earliest and latest occurrence (logs aren't always in order)
+    last_seen: string | null;
+    occurrence_timestamps: string[];     // Most recent occurrences, oldest first (bounded by max_occurrence_timestamps)
     fingerprint: string;
+    frames: StackFrame[];                // First occurrence, innermost frame first
+    causes: ErrorCause[];                // Java "Caused by", Python chained tracebacks, Node [cause]
+    hostname: string | null;             // Source fields from syslog headers
+    app_name: string | null;
+    pid: string | null;
+    attributes: Record<string, string>;  // Extra key/value fields from structured (logfmt, syslog) records
 }
 
 export interface LogStats {
@@ -55,11 +91,80 @@ export interface LogStats {
     total_warnings: number;
     total_info: number;
     unique_errors: number;
+    out_of_range_lines: number;   // Lines left out by ParserOptions.time_range
+    invalid_bytes: number;        // Bytes given to LogParser.process_chunk that weren't valid UTF-8 / UTF-16
+}
+
+export type LogFormatName = 'json' | 'logfmt' | 'syslog' | 'text';
+
+export interface FormatDetection {
+    name: LogFormatName;
+    confidence: number;   // 0.0 - 1.0
+    forced: boolean;      // true when set through ParserOptions.format
+}
+
+export interface TimeHistogram {
+    bucket_seconds: number;
+    start: string | null;                     // Start of the first bucket, null when nothing had a timestamp
+    total: number[];                          // Occurrences per bucket across all errors
+    by_fingerprint: Record<string, number[]>; // Same buckets, for each error in ParseResult.errors
+    untimed: number;                          // Occurrences without a timestamp
+}
+
+export interface Burst {
+    start: string;           // Start of the first busy bucket, RFC 3339 in UTC
+    end: string;             // End of the last busy bucket
+    peak_rate: number;       // Occurrences per minute in the busiest bucket, all errors together
+    baseline_rate: number;   // Usual occurrences per minute across the log
+    fingerprints: string[];  // Errors that spiked, most occurrences first (may include errors outside ParseResult.errors)
 }
 
 export interface ParseResult {
     summary: LogStats;
     errors: ParsedError[];
+    format: FormatDetection;
+    histogram: TimeHistogram;
+    bursts: Burst[];         // Windows where errors arrived well above their usual rate, oldest first
+    compression: 'gzip' | 'zstd' | 'bzip2' | null; // Detected from the bytes given to LogParser.process_chunk
+    decompression_error: string | null; // Set when the compressed input was corrupt or cut short: the result is incomplete
+}
+
+/**
+ * Options accepted by parse_log and the LogParser constructor
+ * Every field is optional - missing fields use the Rust defaults
+ * A field of the wrong type (e.g. a number for time_range.from, an unknown format) discards ALL options:
+ * the error is logged to the console and the parse runs on the defaults
+ */
+export interface ParserOptions {
+    json_fields?: {
+        level?: string[];
+        message?: string[];
+        timestamp?: string[];
+        error_name?: string[];
+        stack?: string[];
+    };
+    format?: LogFormatName;   // Force a format instead of auto-detection
+    detect_lines?: number;    // Leading lines sampled for detection (default 50)
+    // How lines are grouped into multi-line records (default: auto, per format)
+    framing?:
+        | { mode: 'auto' }
+        | { mode: 'timestamp_prefix' }
+        | { mode: 'start_pattern'; pattern: string };
+    max_record_lines?: number; // Upper bound on lines per record (default 10000)
+    group_by_root_cause?: boolean; // Group errors by their deepest cause instead of the wrapper (default false)
+    in_app_prefixes?: string[];    // File path or module prefixes of your own code, e.g. "/srv/app/src", "com.acme."
+    strip_control_sequences?: boolean; // Strip ANSI colours and control characters before classification (default true)
+    default_timezone?: string;     // UTC offset for timestamps without one, e.g. "+02:00" (default UTC)
+    default_year?: number;         // Year for timestamps without one, e.g. syslog (default: current year)
+    max_occurrence_timestamps?: number; // Occurrence timestamps kept per error group (default 100, 0 keeps none)
+    histogram_bucket_seconds?: number;  // Fixed histogram bucket width (default: automatic)
+    histogram_max_buckets?: number;     // Upper bound on buckets; a fixed width is doubled until the log fits (default 60)
+    burst_factor?: number;              // A burst bucket is this many times busier than the median one (default 3)
+    burst_min_count?: number;           // ...and holds at least this many occurrences (default 5)
+    burst_max_buckets?: number;         // Resolution of burst detection (default 1440, minutes over a day)
+    // Only analyse records in [from, to), e.g. { from: "2026-10-16T23:00:00Z", to: "2026-10-16T23:20:00Z" }
+    // Either end may be omitted; records without a timestamp follow the record before them
+    time_range?: { from?: string; to?: string };
 }
 
 // ============================================================================
@@ -90,8 +195,10 @@ export async function initWasm(): Promise<void> {
 /**
  * Read file in chunks using ReadableStream API
  * This prevents loading the entire file into memory at once
+ * Chunks are raw bytes: the parser decodes them and splits lines itself, since a
+ * chunk may end in the middle of a line, a multi-byte character or a compressed block
  */
-async function* readFileInChunks(file: File, chunkSize: number = 10 * 1024 * 1024) {
+async function* readFileInChunks(file: File) {
     const stream = file.stream();
     const reader = stream.getReader();
 
@@ -102,59 +209,25 @@ async function* readFileInChunks(file: File, chunkSize: number = 10 * 1024 * 102
             if (done) break;
 
             // value is a Uint8Array (raw bytes)
-            // Convert to text
-            const text = new TextDecoder().decode(value, { stream: true });
-
-            yield text;
+            yield value;
         }
     } finally {
         reader.releaseLock();
     }
 }
 
-/**
- * Read file line by line using chunks
- * Handles lines that are split across chunks
- */
-async function* readLinesFromFile(file: File, chunkSize: number = 10 * 1024 * 1024) {
-    let buffer = ''; // Buffer for incomplete lines
-
-    for await (const chunk of readFileInChunks(file, chunkSize)) {
-        // Concatenate previous buffer with new chunk
-        buffer += chunk;
-
-        // Split by newlines
-        const lines = buffer.split('\n');
-
-        // The last line might be incomplete, save it for next chunk
-        buffer = lines.pop() || '';
-
-        // Yield all complete lines
-        for (const line of lines) {
-            if (line.trim()) { // Skip empty lines
-                yield line;
-            }
-        }
-    }
-
-    // Process the last line if there's anything left in buffer
-    if (buffer.trim()) {
-        yield buffer;
-    }
-}
-
 // ============================================================================
 // PARSING FUNCTIONS
 // ============================================================================
 
-export async function parseLogFile(file: File): Promise<ParseResult> {
+export async function parseLogFile(file: File, options?: ParserOptions): Promise<ParseResult> {
     if (!wasmInitialized) {
         await initWasm();
     }
 
     try {
         const content = await file.text();
-        const result = parse_log(content);
+        const result = parse_log(content, options);
 
         // Debug logging
         console.log('WASM parse_log result:', result);
@@ -180,13 +253,13 @@ export async function parseLogFile(file: File): Promise<ParseResult> {
     }
 }
 
-export async function parseLogContent(content: string): Promise<ParseResult> {
+export async function parseLogContent(content: string, options?: ParserOptions): Promise<ParseResult> {
     if (!wasmInitialized) {
         await initWasm();
     }
 
     try {
-        const result = parse_log(content);
+        const result = parse_log(content, options);
 
         // Debug logging
         console.log('WASM parse_log result:', result);
@@ -214,15 +287,22 @@ export async function parseLogContent(content: string): Promise<ParseResult> {
 
 /**
  * Parse log file using streaming (for large files >100MB)
- * This processes the file line by line, keeping memory usage constant
+ * This feeds the file to the parser chunk by chunk, keeping memory usage constant
+ * Accepts UTF-8 or UTF-16 (with a byte order mark) text, plain or gzip / zstd / bzip2 compressed
  *
  * @param file - The log file to parse
  * @param onProgress - Optional callback for progress updates (0-100)
+ * @param options - Optional parser options (forced format, JSON field paths, ...)
+ * @param sourceMaps - Optional `.map` files for minified JavaScript bundles in the log
+ * @param proguardMapping - Optional ProGuard / R8 `mapping.txt` for obfuscated JVM stack traces
  * @returns ParseResult with aggregated error statistics
  */
 export async function parseLogFileStreaming(
     file: File,
-    onProgress?: (progress: number) => void
+    onProgress?: (progress: number) => void,
+    options?: ParserOptions,
+    sourceMaps?: File[],
+    proguardMapping?: File
 ): Promise<ParseResult> {
     if (!wasmInitialized) {
         await initWasm();
@@ -233,36 +313,39 @@ export async function parseLogFileStreaming(
         const { LogParser } = await import('../../parser-wasm/pkg/parser_wasm');
 
         // Create parser instance
-        const parser = new LogParser();
+        const parser = new LogParser(options);
+
+        // Maps must be registered before any line is processed
+        for (const map of sourceMaps ?? []) {
+            parser.add_source_map(map.name, new Uint8Array(await map.arrayBuffer()));
+        }
+        if (proguardMapping) {
+            parser.add_proguard_mapping(new Uint8Array(await proguardMapping.arrayBuffer()));
+        }
 
         let processedBytes = 0;
         const totalBytes = file.size;
 
         console.log(`🔄 Starting streaming parse of ${(totalBytes / 1024 / 1024).toFixed(2)}MB file...`);
 
-        let lineCount = 0;
         const startTime = performance.now();
 
-        // Process file line by line
-        for await (const line of readLinesFromFile(file)) {
-            parser.process_line(line);
-            lineCount++;
-
-            // Update progress (estimate based on bytes processed)
-            processedBytes += line.length + 1; // +1 for newline
-
-            // Yield control to browser every 1000 lines to keep UI responsive
-            if (lineCount % 1000 === 0) {
-                const progress = Math.min((processedBytes / totalBytes) * 100, 100);
-                if (onProgress) {
-                    onProgress(progress);
-                }
+        // Hand the raw bytes to the parser, which splits and decodes lines across chunks
+        for await (const chunk of readFileInChunks(file)) {
+            parser.process_chunk(chunk);
+            processedBytes += chunk.length;
 
-                // Yield to browser to update UI and prevent freezing
-                await new Promise(resolve => setTimeout(resolve, 0));
+            if (onProgress) {
+                onProgress(Math.min((processedBytes / totalBytes) * 100, 100));
             }
+
+            // Yield to browser to update UI and prevent freezing
+            await new Promise(resolve => setTimeout(resolve, 0));
         }
 
+        // Flush the decompressor, and the last line when the file doesn't end with a newline
+        parser.finish();
+
         // Get final results
         const result = parser.get_result();
 
@@ -272,7 +355,7 @@ export async function parseLogFileStreaming(
         const endTime = performance.now();
         const duration = ((endTime - startTime) / 1000).toFixed(2);
 
-        console.log(`✅ Streaming parse complete in ${duration}s (${lineCount} lines)`);
+        console.log(`✅ Streaming parse complete in ${duration}s (${result?.summary?.total_lines} lines)`);
 
         // Final progress update
         if (onProgress) {
@@ -399,3 +482,13 @@ export function truncateMessage(message: string, maxLength: number = 100): strin
     if (message.length <= maxLength) return message;
     return message.substring(0, maxLength) + '...';
 }
+
+/**
+ * Format a histogram bucket width as 30s / 5m / 3h / 1d
+ */
+export function formatBucketSize(seconds: number): string {
+    if (seconds < 60) return `${seconds}s`;
+    if (seconds < 3600) return `${seconds / 60}m`;
+    if (seconds < 86400) return `${seconds / 3600}h`;
+    return `${seconds / 86400}d`;
+}